@@ -9,7 +9,6 @@ use ::std::format;
 use ::std::iter::Extend;
 use ::std::iter::Iterator;
 use ::std::matches;
-use ::std::option::Option::Some;
 use ::std::string::String;
 use ::std::string::ToString;
 use ::std::unreachable;
@@ -20,11 +19,14 @@ use ::syn::parse_macro_input;
 use ::syn::Data;
 use ::syn::DataEnum;
 use ::syn::DeriveInput;
+use ::syn::Field;
 use ::syn::Fields;
 use ::syn::FieldsNamed;
 use ::syn::FieldsUnnamed;
 use ::syn::Meta;
 use ::syn::NestedMeta;
+use ::syn::Type;
+use ::syn::TypePath;
 
 #[allow(clippy::too_many_lines)]
 #[proc_macro_derive(AhkFunction)]
@@ -35,104 +37,81 @@ pub fn ahk_function(input: ::proc_macro::TokenStream) -> ::proc_macro::TokenStre
     match input.data {
         Data::Struct(s) => match s.fields {
             Fields::Named(FieldsNamed { named, .. }) => {
-                let argument_idents = named
+                // The names are joined as plain strings, because the string form of a token
+                // stream is wrapped onto new lines once it gets long enough
+                let argument_idents: Vec<String> = named
                     .iter()
                     // Filter out the flags
-                    .filter(|&f| {
-                        let mut include = true;
-                        for attribute in &f.attrs {
-                            if let ::std::result::Result::Ok(Meta::List(list)) =
-                                attribute.parse_meta()
-                            {
-                                for nested in list.nested {
-                                    if let NestedMeta::Meta(Meta::Path(path)) = nested {
-                                        if path.is_ident("long") {
-                                            include = false;
-                                        }
-                                    }
-                                }
-                            }
-                        }
-
-                        include
-                    })
-                    .map(|f| &f.ident);
-
-                let argument_idents_clone = argument_idents.clone();
+                    .filter(|&f| !is_flag(f))
+                    .filter_map(|f| f.ident.as_ref())
+                    .map(ToString::to_string)
+                    .collect();
 
-                let called_arguments = quote! {#(%#argument_idents_clone%) *}
-                    .to_string()
-                    .replace(" %", "%")
-                    .replace("% ", "%")
-                    .replace("%%", "% %");
+                let called_arguments = argument_idents
+                    .iter()
+                    .map(|ident| format!("%{ident}%"))
+                    .collect::<Vec<_>>()
+                    .join(" ");
 
-                let flag_idents = named
+                // Boolean flags are switches which don't take a value
+                let flag_idents: Vec<(String, bool)> = named
                     .iter()
                     // Filter only the flags
-                    .filter(|f| {
-                        let mut include = false;
-
-                        for attribute in &f.attrs {
-                            if let ::std::result::Result::Ok(Meta::List(list)) =
-                                attribute.parse_meta()
-                            {
-                                for nested in list.nested {
-                                    if let NestedMeta::Meta(Meta::Path(path)) = nested {
-                                        // Identify them using the --long flag name
-                                        if path.is_ident("long") {
-                                            include = true;
-                                        }
-                                    }
-                                }
-                            }
-                        }
-
-                        include
+                    .filter(|&f| is_flag(f))
+                    .filter_map(|f| {
+                        f.ident
+                            .as_ref()
+                            .map(|ident| (ident.to_string(), is_bool(f)))
                     })
-                    .map(|f| &f.ident);
+                    .collect();
 
-                let has_flags = flag_idents.clone().count() != 0;
+                let has_flags = !flag_idents.is_empty();
 
                 if has_flags {
-                    let flag_idents_concat = flag_idents.clone();
-                    let argument_idents_concat = argument_idents.clone();
-
                     // Concat the args and flag args if there are flags
-                    let all_arguments =
-                        quote! {#(#argument_idents_concat,) * #(#flag_idents_concat), *}
-                            .to_string();
-
-                    let flag_idents_clone = flag_idents.clone();
-                    let flags = quote! {#(--#flag_idents_clone) *}
-                        .to_string()
-                        .replace("- - ", "--")
-                        .replace('_', "-");
-
-                    let called_flag_arguments = quote! {#(%#flag_idents%) *}
-                        .to_string()
-                        .replace(" %", "%")
-                        .replace("% ", "%")
-                        .replace("%%", "% %");
-
-                    let flags_split: Vec<_> = flags.split(' ').collect();
-                    let flag_args_split: Vec<_> = called_flag_arguments.split(' ').collect();
-                    let mut consolidated_flags: Vec<String> = Vec::new();
-
-                    for (idx, flag) in flags_split.iter().enumerate() {
-                        consolidated_flags.push(format!("{} {}", flag, flag_args_split[idx]));
-                    }
-
-                    let all_flags = consolidated_flags.join(" ");
+                    let all_arguments = argument_idents
+                        .iter()
+                        .chain(flag_idents.iter().map(|(ident, _)| ident))
+                        .cloned()
+                        .collect::<Vec<_>>()
+                        .join(", ");
+
+                    // Switches are only passed when their argument is true, so each one is
+                    // replaced with either the switch itself or nothing before running komorebic
+                    let switches = flag_idents
+                        .iter()
+                        .filter(|(_, switch)| *switch)
+                        .map(|(ident, _)| {
+                            format!(
+                                "    {ident} := {ident} ? \"--{}\" : \"\"\n",
+                                ident.replace('_', "-")
+                            )
+                        })
+                        .collect::<Vec<_>>()
+                        .concat();
+
+                    let all_flags = flag_idents
+                        .iter()
+                        .map(|(ident, switch)| {
+                            if *switch {
+                                format!("%{ident}%")
+                            } else {
+                                format!("--{} %{ident}%", ident.replace('_', "-"))
+                            }
+                        })
+                        .collect::<Vec<_>>()
+                        .join(" ");
 
                     quote! {
                         impl AhkFunction for #name {
                             fn generate_ahk_function() -> String {
                                 ::std::format!(r#"
 {}({}) {{
-    RunWait, komorebic.exe {} {} {}, , Hide
+{}    RunWait, komorebic.exe {} {} {}, , Hide
 }}"#,
                                     ::std::stringify!(#name),
                                     #all_arguments,
+                                    #switches,
                                     ::std::stringify!(#name).to_kebab_case(),
                                     #called_arguments,
                                     #all_flags,
@@ -141,7 +120,7 @@ pub fn ahk_function(input: ::proc_macro::TokenStream) -> ::proc_macro::TokenStre
                         }
                     }
                 } else {
-                    let arguments = quote! {#(#argument_idents), *}.to_string();
+                    let arguments = argument_idents.join(", ");
 
                     quote! {
                         impl AhkFunction for #name {
@@ -167,6 +146,30 @@ pub fn ahk_function(input: ::proc_macro::TokenStream) -> ::proc_macro::TokenStre
     .into()
 }
 
+/// Whether a field is passed as a flag, which is identified using the --long flag name
+fn is_flag(field: &Field) -> bool {
+    let mut flag = false;
+
+    for attribute in &field.attrs {
+        if let ::std::result::Result::Ok(Meta::List(list)) = attribute.parse_meta() {
+            for nested in list.nested {
+                if let NestedMeta::Meta(Meta::Path(path)) = nested {
+                    if path.is_ident("long") {
+                        flag = true;
+                    }
+                }
+            }
+        }
+    }
+
+    flag
+}
+
+/// Whether a field is a bool, which clap takes as a switch without a value
+fn is_bool(field: &Field) -> bool {
+    matches!(&field.ty, Type::Path(TypePath { path, .. }) if path.is_ident("bool"))
+}
+
 #[proc_macro_derive(AhkLibrary)]
 pub fn ahk_library(input: ::proc_macro::TokenStream) -> ::proc_macro::TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
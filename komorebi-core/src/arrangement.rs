@@ -1,4 +1,6 @@
+use std::fmt::Formatter;
 use std::num::NonZeroUsize;
+use std::ops::Range;
 
 use clap::ValueEnum;
 use schemars::JsonSchema;
@@ -7,130 +9,152 @@ use serde::Serialize;
 use strum::Display;
 use strum::EnumString;
 
+use crate::bsp::bsp;
+use crate::centered_master::centered_master;
+use crate::constraints::apply_constraints;
 use crate::custom_layout::Column;
 use crate::custom_layout::ColumnSplit;
 use crate::custom_layout::ColumnSplitWithCapacity;
+use crate::custom_layout::Orientation;
+use crate::resize_ratio::resize_pixels;
+use crate::scrolling::scrolling;
+use crate::BspStrategy;
+use crate::ContainerConstraints;
 use crate::CustomLayout;
 use crate::DefaultLayout;
+use crate::LayoutOptions;
+use crate::Padding;
 use crate::Rect;
+use crate::ResizeRatio;
+use crate::TreeLayout;
 
 pub trait Arrangement {
+    /// Calculates the area of every container, where `scroll_offset` is the offset of the
+    /// viewport from the start of the strip of columns in the scrolling layout
+    #[allow(clippy::too_many_arguments)]
     fn calculate(
         &self,
         area: &Rect,
         len: NonZeroUsize,
-        container_padding: Option<i32>,
+        container_padding: Option<Padding>,
         layout_flip: Option<Axis>,
-        resize_dimensions: &[Option<Rect>],
+        layout_options: LayoutOptions,
+        scroll_offset: i32,
+        resize_dimensions: &[Option<ResizeRatio>],
     ) -> Vec<Rect>;
-}
 
-impl Arrangement for DefaultLayout {
-    #[allow(clippy::too_many_lines)]
-    fn calculate(
+    /// Calculates a layout and then resizes any containers with constraints on the size of their
+    /// windows, taking the space from the containers that they share an edge with
+    #[allow(clippy::too_many_arguments)]
+    fn calculate_constrained(
         &self,
         area: &Rect,
         len: NonZeroUsize,
-        container_padding: Option<i32>,
+        container_padding: Option<Padding>,
         layout_flip: Option<Axis>,
-        resize_dimensions: &[Option<Rect>],
+        layout_options: LayoutOptions,
+        scroll_offset: i32,
+        resize_dimensions: &[Option<ResizeRatio>],
+        constraints: &[Option<ContainerConstraints>],
     ) -> Vec<Rect> {
-        let len = usize::from(len);
-        let mut dimensions = match self {
-            Self::BSP => recursive_fibonacci(
-                0,
-                len,
+        if constraints.iter().all(Option::is_none) {
+            return self.calculate(
                 area,
+                len,
+                container_padding,
                 layout_flip,
-                calculate_resize_adjustments(resize_dimensions),
-            ),
-            Self::Columns => columns(area, len),
-            Self::Rows => rows(area, len),
-            Self::VerticalStack => {
-                let mut layouts: Vec<Rect> = vec![];
+                layout_options,
+                scroll_offset,
+                resize_dimensions,
+            );
+        }
 
-                let primary_right = match len {
-                    1 => area.right,
-                    _ => area.right / 2,
-                };
+        // The constraints are resolved on the unpadded layout, where the edges of neighbouring
+        // containers meet, and the padding is added once they have been resolved
+        let mut layouts = self.calculate(
+            area,
+            len,
+            None,
+            layout_flip,
+            layout_options,
+            scroll_offset,
+            resize_dimensions,
+        );
 
-                let mut main_left = area.left;
-                let mut stack_left = area.left + primary_right;
+        apply_constraints(&mut layouts, constraints, container_padding);
 
-                match layout_flip {
-                    Some(Axis::Horizontal | Axis::HorizontalAndVertical) if len > 1 => {
-                        main_left = main_left + area.right - primary_right;
-                        stack_left = area.left;
-                    }
-                    _ => {}
-                }
+        layouts
+    }
 
-                if len >= 1 {
-                    layouts.push(Rect {
-                        left: main_left,
-                        top: area.top,
-                        right: primary_right,
-                        bottom: area.bottom,
-                    });
-
-                    if len > 1 {
-                        layouts.append(&mut rows(
-                            &Rect {
-                                left: stack_left,
-                                top: area.top,
-                                right: area.right - primary_right,
-                                bottom: area.bottom,
-                            },
-                            len - 1,
-                        ));
-                    }
-                }
+    /// Whether the containers laid out by this arrangement always cover the whole of the area
+    /// without extending beyond it
+    fn covers_area(&self, _len: NonZeroUsize, _layout_options: LayoutOptions) -> bool {
+        true
+    }
+}
 
+impl Arrangement for DefaultLayout {
+    #[allow(clippy::too_many_lines)]
+    fn calculate(
+        &self,
+        area: &Rect,
+        len: NonZeroUsize,
+        container_padding: Option<Padding>,
+        layout_flip: Option<Axis>,
+        layout_options: LayoutOptions,
+        scroll_offset: i32,
+        resize_dimensions: &[Option<ResizeRatio>],
+    ) -> Vec<Rect> {
+        let len = usize::from(len);
+        let resize_dimensions = &resize_pixels(resize_dimensions, area);
+        let mut dimensions = match self {
+            Self::BSP => match layout_options.bsp_strategy {
+                BspStrategy::Dwindle => recursive_fibonacci(
+                    0,
+                    len,
+                    area,
+                    layout_flip,
+                    calculate_resize_adjustments(resize_dimensions),
+                ),
+                BspStrategy::Spiral | BspStrategy::Balanced => bsp(
+                    area,
+                    len,
+                    layout_options.bsp_strategy,
+                    layout_flip,
+                    resize_dimensions,
+                ),
+            },
+            Self::Columns => {
+                let mut layouts = columns(area, len);
+                resize_columns(&mut layouts, resize_dimensions);
                 layouts
             }
-            Self::HorizontalStack => {
-                let mut layouts: Vec<Rect> = vec![];
-
-                let bottom = match len {
-                    1 => area.bottom,
-                    _ => area.bottom / 2,
-                };
-
-                let mut main_top = area.top;
-                let mut stack_top = area.top + bottom;
-
-                match layout_flip {
-                    Some(Axis::Vertical | Axis::HorizontalAndVertical) if len > 1 => {
-                        main_top = main_top + area.bottom - bottom;
-                        stack_top = area.top;
-                    }
-                    _ => {}
-                }
-
-                if len >= 1 {
-                    layouts.push(Rect {
-                        left: area.left,
-                        top: main_top,
-                        right: area.right,
-                        bottom,
-                    });
-
-                    if len > 1 {
-                        layouts.append(&mut columns(
-                            &Rect {
-                                left: area.left,
-                                top: stack_top,
-                                right: area.right,
-                                bottom: area.bottom - bottom,
-                            },
-                            len - 1,
-                        ));
-                    }
-                }
-
+            Self::Rows => {
+                let mut layouts = rows(area, len);
+                resize_rows(&mut layouts, resize_dimensions);
                 layouts
             }
-            Self::UltrawideVerticalStack => ultrawide(area, len, layout_flip, resize_dimensions),
+            Self::VerticalStack => {
+                vertical_stack(area, len, layout_flip, layout_options, resize_dimensions)
+            }
+            Self::HorizontalStack => {
+                horizontal_stack(area, len, layout_flip, layout_options, resize_dimensions)
+            }
+            Self::UltrawideVerticalStack => {
+                ultrawide(area, len, layout_flip, layout_options, resize_dimensions)
+            }
+            Self::Grid => grid(area, len, layout_flip, resize_dimensions),
+            Self::Scrolling => scrolling(
+                area,
+                len,
+                layout_flip,
+                layout_options,
+                scroll_offset,
+                resize_dimensions,
+            ),
+            Self::CenteredMaster => {
+                centered_master(area, len, layout_flip, layout_options, resize_dimensions)
+            }
         };
 
         dimensions
@@ -139,17 +163,60 @@ impl Arrangement for DefaultLayout {
 
         dimensions
     }
+
+    fn covers_area(&self, len: NonZeroUsize, layout_options: LayoutOptions) -> bool {
+        match self {
+            // Columns are placed off-screen when they don't fit in the viewport, and a short
+            // strip of columns leaves the rest of the viewport empty
+            Self::Scrolling => false,
+            Self::CenteredMaster => {
+                len.get() > 1 || layout_options.single_container_width.is_none()
+            }
+            _ => true,
+        }
+    }
 }
 
 impl Arrangement for CustomLayout {
+    #[allow(clippy::only_used_in_recursion)]
     fn calculate(
         &self,
         area: &Rect,
         len: NonZeroUsize,
-        container_padding: Option<i32>,
-        _layout_flip: Option<Axis>,
-        _resize_dimensions: &[Option<Rect>],
+        container_padding: Option<Padding>,
+        layout_flip: Option<Axis>,
+        layout_options: LayoutOptions,
+        scroll_offset: i32,
+        resize_dimensions: &[Option<ResizeRatio>],
     ) -> Vec<Rect> {
+        if matches!(self.orientation(), Orientation::Rows) {
+            let resize_dimensions: Vec<_> = resize_dimensions
+                .iter()
+                .map(|resize| resize.as_ref().map(ResizeRatio::transposed))
+                .collect();
+
+            // Flips are applied after transposing back so that they stay on the requested axis
+            let mut dimensions: Vec<Rect> = self
+                .transposed()
+                .calculate(
+                    &area.transposed(),
+                    len,
+                    container_padding.as_ref().map(Padding::transposed),
+                    None,
+                    layout_options,
+                    scroll_offset,
+                    &resize_dimensions,
+                )
+                .iter()
+                .map(Rect::transposed)
+                .collect();
+
+            flip_layouts(&mut dimensions, area, layout_flip);
+
+            return dimensions;
+        }
+
+        let resize_dimensions = &resize_pixels(resize_dimensions, area);
         let mut dimensions = vec![];
         let container_count = len.get();
 
@@ -179,83 +246,62 @@ impl Arrangement for CustomLayout {
                 Option::from(1)
             };
 
-            #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
-            let primary_right = self.primary_width_percentage().map_or_else(
-                || area.right / self.len() as i32,
-                |percentage| (area.right / 100) * percentage as i32,
-            );
+            // If we are offsetting a tertiary column for which the threshold
+            // has not yet been met, that final tertiary column is not laid out
+            let active_columns = self.len() - offset.unwrap_or(0);
+            let column_widths = self.column_widths(area.right, active_columns);
+            let mut left = area.left;
+
+            for (definition, width) in self.iter().zip(column_widths) {
+                let column_area = Rect {
+                    left,
+                    top: area.top,
+                    right: width,
+                    bottom: area.bottom,
+                };
 
-            for (idx, column) in self.iter().enumerate() {
-                // If we are offsetting a tertiary column for which the threshold
-                // has not yet been met, this loop should not run for that final
-                // tertiary column
-                if idx < self.len() - offset.unwrap_or(0) {
-                    let column_area = if idx == 0 {
-                        Self::column_area_with_last(self.len(), area, primary_right, None, offset)
-                    } else {
-                        Self::column_area_with_last(
-                            self.len(),
-                            area,
-                            primary_right,
-                            Option::from(dimensions[self.first_container_idx(idx - 1)]),
-                            offset,
-                        )
-                    };
+                left += width;
 
-                    match column {
-                        Column::Primary(Option::Some(_)) => {
-                            let main_column_area = if idx == 0 {
-                                Self::main_column_area(area, primary_right, None)
-                            } else {
-                                Self::main_column_area(
-                                    area,
-                                    primary_right,
-                                    Option::from(dimensions[self.first_container_idx(idx - 1)]),
-                                )
-                            };
-
-                            dimensions.push(main_column_area);
+                match definition.column {
+                    Column::Primary(_) | Column::Secondary(None) => {
+                        dimensions.push(column_area);
+                    }
+                    Column::Secondary(Some(split)) => match split {
+                        ColumnSplitWithCapacity::Horizontal(capacity) => {
+                            let mut rows = rows(&column_area, capacity);
+                            dimensions.append(&mut rows);
                         }
-                        Column::Primary(None) | Column::Secondary(None) => {
-                            dimensions.push(column_area);
+                        ColumnSplitWithCapacity::Vertical(capacity) => {
+                            let mut columns = columns(&column_area, capacity);
+                            dimensions.append(&mut columns);
                         }
-                        Column::Secondary(Some(split)) => match split {
-                            ColumnSplitWithCapacity::Horizontal(capacity) => {
-                                let mut rows = rows(&column_area, *capacity);
+                    },
+                    Column::Tertiary(split) => {
+                        let remaining = container_count - tertiary_trigger_threshold;
+
+                        match split {
+                            ColumnSplit::Horizontal => {
+                                let mut rows = rows(&column_area, remaining);
                                 dimensions.append(&mut rows);
                             }
-                            ColumnSplitWithCapacity::Vertical(capacity) => {
-                                let mut columns = columns(&column_area, *capacity);
+                            ColumnSplit::Vertical => {
+                                let mut columns = columns(&column_area, remaining);
                                 dimensions.append(&mut columns);
                             }
-                        },
-                        Column::Tertiary(split) => {
-                            let column_area = Self::column_area_with_last(
-                                self.len(),
-                                area,
-                                primary_right,
-                                Option::from(dimensions[self.first_container_idx(idx - 1)]),
-                                offset,
-                            );
-
-                            let remaining = container_count - tertiary_trigger_threshold;
-
-                            match split {
-                                ColumnSplit::Horizontal => {
-                                    let mut rows = rows(&column_area, remaining);
-                                    dimensions.append(&mut rows);
-                                }
-                                ColumnSplit::Vertical => {
-                                    let mut columns = columns(&column_area, remaining);
-                                    dimensions.append(&mut columns);
-                                }
-                            }
                         }
                     }
                 }
             }
         }
 
+        resize_custom_columns(
+            &mut dimensions,
+            &self.column_containers(container_count),
+            resize_dimensions,
+        );
+
+        flip_layouts(&mut dimensions, area, layout_flip);
+
         dimensions
             .iter_mut()
             .for_each(|l| l.add_padding(container_padding));
@@ -264,6 +310,29 @@ impl Arrangement for CustomLayout {
     }
 }
 
+impl Arrangement for TreeLayout {
+    fn calculate(
+        &self,
+        area: &Rect,
+        len: NonZeroUsize,
+        container_padding: Option<Padding>,
+        layout_flip: Option<Axis>,
+        _layout_options: LayoutOptions,
+        _scroll_offset: i32,
+        // Resizing is rejected for tree layouts, as containers are sized by the ratios of the tree
+        _resize_dimensions: &[Option<ResizeRatio>],
+    ) -> Vec<Rect> {
+        let mut dimensions = self.layouts(area, len.get());
+        flip_layouts(&mut dimensions, area, layout_flip);
+
+        for layout in &mut dimensions {
+            layout.add_padding(container_padding);
+        }
+
+        dimensions
+    }
+}
+
 #[derive(
     Clone, Copy, Debug, Serialize, Deserialize, Display, EnumString, ValueEnum, JsonSchema,
 )]
@@ -274,8 +343,189 @@ pub enum Axis {
     HorizontalAndVertical,
 }
 
+/// A way in which the rects calculated by an arrangement fail to tile the area they were
+/// calculated for
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type", content = "content")]
+pub enum LayoutFinding {
+    /// A different number of rects was calculated than the number of containers
+    CountMismatch { expected: usize, actual: usize },
+    /// A container has a width or height of zero or less once its padding is applied
+    EmptyContainer { container: usize, rect: Rect },
+    /// A container extends beyond the edges of the area
+    OutsideArea { container: usize, rect: Rect },
+    /// Two containers cover some of the same space
+    Overlap { first: usize, second: usize },
+    /// Some of the area isn't covered by any container
+    Gap { pixels: i64, bounds: Rect },
+}
+
+impl std::fmt::Display for LayoutFinding {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::CountMismatch { expected, actual } => write!(
+                f,
+                "{actual} containers were laid out when {expected} were expected"
+            ),
+            Self::EmptyContainer { container, rect } => write!(
+                f,
+                "container {container} has a size of {}x{}",
+                rect.right, rect.bottom
+            ),
+            Self::OutsideArea { container, rect } => write!(
+                f,
+                "container {container} at {}x{}+{}+{} extends beyond the area",
+                rect.right, rect.bottom, rect.left, rect.top
+            ),
+            Self::Overlap { first, second } => {
+                write!(f, "containers {first} and {second} overlap")
+            }
+            Self::Gap { pixels, bounds } => write!(
+                f,
+                "{pixels} pixels within {}x{}+{}+{} are not covered by any container",
+                bounds.right, bounds.bottom, bounds.left, bounds.top
+            ),
+        }
+    }
+}
+
+/// Checks the rects calculated by an arrangement against the area and the number of containers
+/// that they were calculated for, where `container_padding` is the padding that was applied
+///
+/// Gaps and containers outside of the area are only reported when `covers_area` is set, as some
+/// layouts leave space empty or place containers off-screen by design
 #[must_use]
-fn columns(area: &Rect, len: usize) -> Vec<Rect> {
+pub fn check_layouts(
+    area: &Rect,
+    len: NonZeroUsize,
+    container_padding: Option<Padding>,
+    layouts: &[Rect],
+    covers_area: bool,
+) -> Vec<LayoutFinding> {
+    let mut findings = vec![];
+
+    if layouts.len() != len.get() {
+        findings.push(LayoutFinding::CountMismatch {
+            expected: len.get(),
+            actual: layouts.len(),
+        });
+    }
+
+    // The padding is taken off again so that neighbouring containers meet at a shared edge
+    let padding = container_padding.unwrap_or_default();
+    let unpadded = layouts
+        .iter()
+        .map(|layout| Rect {
+            left: layout.left - padding.left,
+            top: layout.top - padding.top,
+            right: layout.right + padding.left + padding.right,
+            bottom: layout.bottom + padding.top + padding.bottom,
+        })
+        .collect::<Vec<_>>();
+
+    for (container, (layout, rect)) in layouts.iter().zip(&unpadded).enumerate() {
+        if layout.right <= 0 || layout.bottom <= 0 {
+            findings.push(LayoutFinding::EmptyContainer {
+                container,
+                rect: *layout,
+            });
+        }
+
+        if covers_area && !area.contains(rect) {
+            findings.push(LayoutFinding::OutsideArea {
+                container,
+                rect: *layout,
+            });
+        }
+    }
+
+    for (first, rect) in unpadded.iter().enumerate() {
+        for (second, other) in unpadded.iter().enumerate().skip(first + 1) {
+            if rect.intersects(other) {
+                findings.push(LayoutFinding::Overlap { first, second });
+            }
+        }
+    }
+
+    if covers_area {
+        findings.extend(find_gap(area, &unpadded));
+    }
+
+    findings
+}
+
+/// Splits the area into cells along every edge of the containers, and collects the cells which
+/// are not covered by any container
+#[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+fn find_gap(area: &Rect, layouts: &[Rect]) -> Option<LayoutFinding> {
+    let tolerance = layouts.len() as i32;
+    let xs = cut_points(
+        area.left,
+        area.right,
+        layouts.iter().map(|layout| (layout.left, layout.right)),
+    );
+    let ys = cut_points(
+        area.top,
+        area.bottom,
+        layouts.iter().map(|layout| (layout.top, layout.bottom)),
+    );
+
+    let mut pixels = 0;
+    let mut bounds: Option<(i32, i32, i32, i32)> = None;
+
+    for x in xs.windows(2) {
+        for y in ys.windows(2) {
+            let cell = Rect {
+                left: x[0],
+                top: y[0],
+                right: x[1] - x[0],
+                bottom: y[1] - y[0],
+            };
+
+            // Dividing the area between containers can leave up to one pixel per container
+            // uncovered, so strips which are thinner than that are not treated as gaps
+            if cell.right < tolerance
+                || cell.bottom < tolerance
+                || layouts.iter().any(|layout| layout.contains(&cell))
+            {
+                continue;
+            }
+
+            pixels += i64::from(cell.right) * i64::from(cell.bottom);
+            bounds = Option::from(bounds.map_or((x[0], y[0], x[1], y[1]), |(l, t, r, b)| {
+                (l.min(x[0]), t.min(y[0]), r.max(x[1]), b.max(y[1]))
+            }));
+        }
+    }
+
+    bounds.map(|(left, top, right, bottom)| LayoutFinding::Gap {
+        pixels,
+        bounds: Rect {
+            left,
+            top,
+            right: right - left,
+            bottom: bottom - top,
+        },
+    })
+}
+
+/// The sorted start and end points of every span along one axis, clamped to the area
+fn cut_points(start: i32, size: i32, spans: impl Iterator<Item = (i32, i32)>) -> Vec<i32> {
+    let end = start + size;
+    let mut points = vec![start, end];
+
+    for (from, length) in spans {
+        points.push(from.clamp(start, end));
+        points.push((from + length).clamp(start, end));
+    }
+
+    points.sort_unstable();
+    points.dedup();
+    points
+}
+
+#[must_use]
+pub(crate) fn columns(area: &Rect, len: usize) -> Vec<Rect> {
     #[allow(clippy::cast_possible_wrap, clippy::cast_possible_truncation)]
     let right = area.right / len as i32;
     let mut left = 0;
@@ -296,7 +546,7 @@ fn columns(area: &Rect, len: usize) -> Vec<Rect> {
 }
 
 #[must_use]
-fn rows(area: &Rect, len: usize) -> Vec<Rect> {
+pub(crate) fn rows(area: &Rect, len: usize) -> Vec<Rect> {
     #[allow(clippy::cast_possible_wrap, clippy::cast_possible_truncation)]
     let bottom = area.bottom / len as i32;
     let mut top = 0;
@@ -316,6 +566,343 @@ fn rows(area: &Rect, len: usize) -> Vec<Rect> {
     layouts
 }
 
+/// Returns the number of columns and rows of a near-square grid for `len` containers
+#[must_use]
+pub const fn grid_dimensions(len: usize) -> (usize, usize) {
+    let mut columns = 1;
+    while columns * columns < len {
+        columns += 1;
+    }
+
+    (columns, len.div_ceil(columns))
+}
+
+#[must_use]
+fn grid(
+    area: &Rect,
+    len: usize,
+    layout_flip: Option<Axis>,
+    resize_dimensions: &[Option<Rect>],
+) -> Vec<Rect> {
+    let (column_count, row_count) = grid_dimensions(len);
+    let mut row_areas = rows(area, row_count);
+
+    // The last row takes whatever is left over and stretches it across the full width
+    let row_range = |row: usize| {
+        let start = row * column_count;
+        start..len.min(start + column_count)
+    };
+
+    for row in 0..row_count - 1 {
+        let delta: i32 = resize_dimensions
+            .get(row_range(row))
+            .unwrap_or_default()
+            .iter()
+            .flatten()
+            .map(|resize| resize.bottom)
+            .chain(
+                resize_dimensions
+                    .get(row_range(row + 1))
+                    .unwrap_or_default()
+                    .iter()
+                    .flatten()
+                    .map(|resize| resize.top),
+            )
+            .sum();
+
+        move_horizontal_edge(&mut row_areas, row, delta);
+    }
+
+    let mut layouts: Vec<Rect> = vec![];
+    for (row, row_area) in row_areas.iter().enumerate() {
+        let mut row_layouts = columns(row_area, row_range(row).len());
+        resize_columns(
+            &mut row_layouts,
+            resize_dimensions.get(row_range(row)).unwrap_or_default(),
+        );
+
+        layouts.append(&mut row_layouts);
+    }
+
+    flip_layouts(&mut layouts, area, layout_flip);
+
+    layouts
+}
+
+#[must_use]
+fn vertical_stack(
+    area: &Rect,
+    len: usize,
+    layout_flip: Option<Axis>,
+    layout_options: LayoutOptions,
+    resize_dimensions: &[Option<Rect>],
+) -> Vec<Rect> {
+    let masters = layout_options.masters(len);
+
+    let primary_right = if len > masters {
+        layout_options.main_size(area.right)
+    } else {
+        area.right
+    };
+
+    let mut layouts = rows(
+        &Rect {
+            left: area.left,
+            top: area.top,
+            right: primary_right,
+            bottom: area.bottom,
+        },
+        masters,
+    );
+
+    if len > masters {
+        layouts.append(&mut rows(
+            &Rect {
+                left: area.left + primary_right,
+                top: area.top,
+                right: area.right - primary_right,
+                bottom: area.bottom,
+            },
+            len - masters,
+        ));
+
+        // The edge between the main area and the stack is shared by every container
+        let delta: i32 = resize_dimensions
+            .iter()
+            .take(len)
+            .enumerate()
+            .filter_map(|(i, resize)| resize.map(|r| if i < masters { r.right } else { r.left }))
+            .sum();
+
+        let (main, stack) = layouts.split_at_mut(masters);
+        for rect in main {
+            rect.right += delta;
+        }
+
+        for rect in stack {
+            rect.left += delta;
+            rect.right -= delta;
+        }
+    }
+
+    let (main, stack) = layouts.split_at_mut(masters);
+    let (main_resize, stack_resize) =
+        resize_dimensions.split_at(masters.min(resize_dimensions.len()));
+    resize_rows(main, main_resize);
+    resize_rows(stack, stack_resize);
+
+    if len > masters {
+        flip_layouts(
+            &mut layouts,
+            area,
+            match layout_flip {
+                Some(Axis::Horizontal | Axis::HorizontalAndVertical) => Some(Axis::Horizontal),
+                _ => None,
+            },
+        );
+    }
+
+    layouts
+}
+
+#[must_use]
+fn horizontal_stack(
+    area: &Rect,
+    len: usize,
+    layout_flip: Option<Axis>,
+    layout_options: LayoutOptions,
+    resize_dimensions: &[Option<Rect>],
+) -> Vec<Rect> {
+    let masters = layout_options.masters(len);
+
+    let primary_bottom = if len > masters {
+        layout_options.main_size(area.bottom)
+    } else {
+        area.bottom
+    };
+
+    let mut layouts = columns(
+        &Rect {
+            left: area.left,
+            top: area.top,
+            right: area.right,
+            bottom: primary_bottom,
+        },
+        masters,
+    );
+
+    if len > masters {
+        layouts.append(&mut columns(
+            &Rect {
+                left: area.left,
+                top: area.top + primary_bottom,
+                right: area.right,
+                bottom: area.bottom - primary_bottom,
+            },
+            len - masters,
+        ));
+
+        // The edge between the main area and the stack is shared by every container
+        let delta: i32 = resize_dimensions
+            .iter()
+            .take(len)
+            .enumerate()
+            .filter_map(|(i, resize)| resize.map(|r| if i < masters { r.bottom } else { r.top }))
+            .sum();
+
+        let (main, stack) = layouts.split_at_mut(masters);
+        for rect in main {
+            rect.bottom += delta;
+        }
+
+        for rect in stack {
+            rect.top += delta;
+            rect.bottom -= delta;
+        }
+    }
+
+    let (main, stack) = layouts.split_at_mut(masters);
+    let (main_resize, stack_resize) =
+        resize_dimensions.split_at(masters.min(resize_dimensions.len()));
+    resize_columns(main, main_resize);
+    resize_columns(stack, stack_resize);
+
+    if len > masters {
+        flip_layouts(
+            &mut layouts,
+            area,
+            match layout_flip {
+                Some(Axis::Vertical | Axis::HorizontalAndVertical) => Some(Axis::Vertical),
+                _ => None,
+            },
+        );
+    }
+
+    layouts
+}
+
+/// Applies the left and right resize adjustments of containers laid out as columns
+fn resize_columns(layouts: &mut [Rect], resize_dimensions: &[Option<Rect>]) {
+    for (i, resize) in resize_dimensions.iter().enumerate().take(layouts.len()) {
+        if let Some(resize) = resize {
+            if i > 0 {
+                move_vertical_edge(layouts, i - 1, resize.left);
+            }
+
+            if i + 1 < layouts.len() {
+                move_vertical_edge(layouts, i, resize.right);
+            }
+        }
+    }
+}
+
+/// Applies the top and bottom resize adjustments of containers laid out as rows
+pub(crate) fn resize_rows(layouts: &mut [Rect], resize_dimensions: &[Option<Rect>]) {
+    for (i, resize) in resize_dimensions.iter().enumerate().take(layouts.len()) {
+        if let Some(resize) = resize {
+            if i > 0 {
+                move_horizontal_edge(layouts, i - 1, resize.top);
+            }
+
+            if i + 1 < layouts.len() {
+                move_horizontal_edge(layouts, i, resize.bottom);
+            }
+        }
+    }
+}
+
+/// Moves the edge shared by the layouts at `idx` and `idx + 1` to the right by `delta`
+fn move_vertical_edge(layouts: &mut [Rect], idx: usize, delta: i32) {
+    layouts[idx].right += delta;
+    layouts[idx + 1].left += delta;
+    layouts[idx + 1].right -= delta;
+}
+
+/// Moves the edge shared by the layouts at `idx` and `idx + 1` down by `delta`
+fn move_horizontal_edge(layouts: &mut [Rect], idx: usize, delta: i32) {
+    layouts[idx].bottom += delta;
+    layouts[idx + 1].top += delta;
+    layouts[idx + 1].bottom -= delta;
+}
+
+/// Applies resize adjustments to the containers of a custom layout, where the edge between two
+/// columns is moved by the adjustments of every container on either side which touches it
+fn resize_custom_columns(
+    layouts: &mut [Rect],
+    columns: &[(Range<usize>, ColumnSplit)],
+    resize_dimensions: &[Option<Rect>],
+) {
+    let resize_for = |containers: &Range<usize>| -> &[Option<Rect>] {
+        let len = resize_dimensions.len();
+        &resize_dimensions[containers.start.min(len)..containers.end.min(len)]
+    };
+
+    for (containers, split) in columns {
+        let resize = resize_for(containers);
+
+        match split {
+            ColumnSplit::Horizontal => resize_rows(&mut layouts[containers.clone()], resize),
+            ColumnSplit::Vertical => resize_columns(&mut layouts[containers.clone()], resize),
+        }
+    }
+
+    for pair in columns.windows(2) {
+        let (previous, previous_split) = &pair[0];
+        let (next, next_split) = &pair[1];
+
+        // Only the last and first containers of vertically split columns touch their neighbours
+        let previous_edge = match previous_split {
+            ColumnSplit::Horizontal => previous.clone(),
+            ColumnSplit::Vertical => previous.end - 1..previous.end,
+        };
+
+        let next_edge = match next_split {
+            ColumnSplit::Horizontal => next.clone(),
+            ColumnSplit::Vertical => next.start..next.start + 1,
+        };
+
+        let delta: i32 = previous_edge
+            .clone()
+            .filter_map(|idx| resize_dimensions.get(idx).copied().flatten())
+            .map(|resize| resize.right)
+            .chain(
+                next_edge
+                    .clone()
+                    .filter_map(|idx| resize_dimensions.get(idx).copied().flatten())
+                    .map(|resize| resize.left),
+            )
+            .sum();
+
+        for idx in previous_edge {
+            layouts[idx].right += delta;
+        }
+
+        for idx in next_edge {
+            layouts[idx].left += delta;
+            layouts[idx].right -= delta;
+        }
+    }
+}
+
+/// Mirrors layouts calculated in their unflipped positions within the given area
+pub(crate) fn flip_layouts(layouts: &mut [Rect], area: &Rect, layout_flip: Option<Axis>) {
+    for rect in layouts {
+        if matches!(
+            layout_flip,
+            Some(Axis::Horizontal | Axis::HorizontalAndVertical)
+        ) {
+            rect.left = 2 * area.left + area.right - rect.left - rect.right;
+        }
+
+        if matches!(
+            layout_flip,
+            Some(Axis::Vertical | Axis::HorizontalAndVertical)
+        ) {
+            rect.top = 2 * area.top + area.bottom - rect.top - rect.bottom;
+        }
+    }
+}
+
 fn calculate_resize_adjustments(resize_dimensions: &[Option<Rect>]) -> Vec<Option<Rect>> {
     let mut resize_adjustments = resize_dimensions.to_vec();
 
@@ -460,12 +1047,7 @@ fn recursive_fibonacci(
     if count == 0 {
         vec![]
     } else if count == 1 {
-        vec![Rect {
-            left: resized.left,
-            top: resized.top,
-            right: resized.right,
-            bottom: resized.bottom,
-        }]
+        vec![resized]
     } else if idx % 2 != 0 {
         let mut res = vec![Rect {
             left: resized.left,
@@ -557,9 +1139,9 @@ fn calculate_ultrawide_adjustment(resize_dimensions: &[Option<Rect>]) -> Vec<Rec
             for (i, rect) in resize_dimensions[2..].iter().enumerate() {
                 if let Some(rect) = rect {
                     resize_right(primary, rect.left);
-                    tertiary
-                        .iter_mut()
-                        .for_each(|vertical_element| resize_left(vertical_element, rect.left));
+                    for vertical_element in &mut *tertiary {
+                        resize_left(vertical_element, rect.left);
+                    }
 
                     // Containers in stack except first can be resized up displacing container
                     // above them
@@ -577,7 +1159,7 @@ fn calculate_ultrawide_adjustment(resize_dimensions: &[Option<Rect>]) -> Vec<Rec
                 }
             }
         }
-    };
+    }
 
     result
 }
@@ -604,13 +1186,19 @@ fn ultrawide(
     area: &Rect,
     len: usize,
     layout_flip: Option<Axis>,
+    layout_options: LayoutOptions,
     resize_dimensions: &[Option<Rect>],
 ) -> Vec<Rect> {
+    // All of the masters are laid out as if they were a single primary container, and are
+    // then split into rows within the primary column once any resize adjustments are applied
+    let masters = layout_options.masters(len);
+    let len = len - masters + 1;
+
     let mut layouts: Vec<Rect> = vec![];
 
     let primary_right = match len {
         1 => area.right,
-        _ => area.right / 2,
+        _ => layout_options.main_size(area.right),
     };
 
     let secondary_right = match len {
@@ -682,7 +1270,7 @@ fn ultrawide(
         }
     }
 
-    let adjustment = calculate_ultrawide_adjustment(resize_dimensions);
+    let adjustment = calculate_ultrawide_adjustment(&collapse_masters(resize_dimensions, masters));
     layouts
         .iter_mut()
         .zip(adjustment.iter())
@@ -693,5 +1281,31 @@ fn ultrawide(
             layout.right += adjustment.right;
         });
 
-    layouts
+    let primary = layouts.remove(0);
+    let mut master_layouts = rows(&primary, masters);
+    master_layouts.append(&mut layouts);
+
+    master_layouts
+}
+
+/// Merges the horizontal resize adjustments of all masters into a single primary adjustment
+fn collapse_masters(resize_dimensions: &[Option<Rect>], masters: usize) -> Vec<Option<Rect>> {
+    if resize_dimensions.is_empty() {
+        return vec![];
+    }
+
+    let primary = resize_dimensions.iter().take(masters).flatten().fold(
+        None,
+        |primary: Option<Rect>, resize| {
+            let mut primary = primary.unwrap_or_default();
+            primary.left += resize.left;
+            primary.right += resize.right;
+            Option::from(primary)
+        },
+    );
+
+    let mut collapsed = vec![primary];
+    collapsed.extend(resize_dimensions.iter().skip(masters));
+
+    collapsed
 }
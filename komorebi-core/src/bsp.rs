@@ -0,0 +1,241 @@
+use crate::arrangement::flip_layouts;
+use crate::direction::Geometric;
+use crate::direction::NAVIGATION_AREA;
+use crate::Axis;
+use crate::BspStrategy;
+use crate::OperationDirection;
+use crate::Rect;
+
+/// The ways that an area can be split in two
+#[derive(Clone, Copy)]
+enum Split {
+    /// Into a left and a right half
+    Horizontal,
+    /// Into a top and a bottom half
+    Vertical,
+}
+
+impl Split {
+    const fn for_depth(depth: usize) -> Self {
+        if depth.is_multiple_of(2) {
+            Self::Horizontal
+        } else {
+            Self::Vertical
+        }
+    }
+}
+
+enum BspNode {
+    Container(usize),
+    Branch {
+        split: Split,
+        first: Box<BspNode>,
+        second: Box<BspNode>,
+    },
+}
+
+impl BspNode {
+    fn new(len: usize, bsp_strategy: BspStrategy) -> Self {
+        match bsp_strategy {
+            BspStrategy::Dwindle | BspStrategy::Spiral => Self::chain(0, len, bsp_strategy),
+            BspStrategy::Balanced => {
+                let mut root = Self::Container(0);
+
+                for idx in 1..len {
+                    let mut leaves = vec![];
+                    root.leaves(0, &mut leaves);
+
+                    // The shallowest container is always the largest one
+                    if let Some((target, _)) = leaves
+                        .into_iter()
+                        .min_by_key(|(target, depth)| (*depth, *target))
+                    {
+                        root.split_container(target, idx, 0);
+                    }
+                }
+
+                root
+            }
+        }
+    }
+
+    fn chain(idx: usize, len: usize, bsp_strategy: BspStrategy) -> Self {
+        if idx + 1 >= len {
+            return Self::Container(idx);
+        }
+
+        let container = Box::new(Self::Container(idx));
+        let remaining = Box::new(Self::chain(idx + 1, len, bsp_strategy));
+
+        // Spiral puts every third and fourth container after the remaining area, so that the
+        // containers rotate clockwise around the center instead of dwindling to the bottom-right
+        let (first, second) = if matches!(bsp_strategy, BspStrategy::Spiral) && idx % 4 >= 2 {
+            (remaining, container)
+        } else {
+            (container, remaining)
+        };
+
+        Self::Branch {
+            split: Split::for_depth(idx),
+            first,
+            second,
+        }
+    }
+
+    fn leaves(&self, depth: usize, leaves: &mut Vec<(usize, usize)>) {
+        match self {
+            Self::Container(idx) => leaves.push((*idx, depth)),
+            Self::Branch { first, second, .. } => {
+                first.leaves(depth + 1, leaves);
+                second.leaves(depth + 1, leaves);
+            }
+        }
+    }
+
+    fn split_container(&mut self, target: usize, idx: usize, depth: usize) {
+        match self {
+            Self::Container(existing) if *existing == target => {
+                *self = Self::Branch {
+                    split: Split::for_depth(depth),
+                    first: Box::new(Self::Container(target)),
+                    second: Box::new(Self::Container(idx)),
+                };
+            }
+            Self::Container(_) => {}
+            Self::Branch { first, second, .. } => {
+                first.split_container(target, idx, depth + 1);
+                second.split_container(target, idx, depth + 1);
+            }
+        }
+    }
+
+    /// The containers which have an edge touching the given edge of this node
+    fn containers_on_edge(&self, edge: OperationDirection, containers: &mut Vec<usize>) {
+        match self {
+            Self::Container(idx) => containers.push(*idx),
+            Self::Branch {
+                split,
+                first,
+                second,
+            } => match (split, edge) {
+                (Split::Horizontal, OperationDirection::Left)
+                | (Split::Vertical, OperationDirection::Up) => {
+                    first.containers_on_edge(edge, containers);
+                }
+                (Split::Horizontal, OperationDirection::Right)
+                | (Split::Vertical, OperationDirection::Down) => {
+                    second.containers_on_edge(edge, containers);
+                }
+                _ => {
+                    first.containers_on_edge(edge, containers);
+                    second.containers_on_edge(edge, containers);
+                }
+            },
+        }
+    }
+
+    // The edge between the two halves of a branch is moved by the resize adjustments of every
+    // container on either side of it which has an edge touching it
+    fn layout(&self, area: &Rect, resize_dimensions: &[Option<Rect>], layouts: &mut [Rect]) {
+        match self {
+            Self::Container(idx) => {
+                if let Some(layout) = layouts.get_mut(*idx) {
+                    *layout = *area;
+                }
+            }
+            Self::Branch {
+                split,
+                first,
+                second,
+            } => {
+                let delta = |node: &Self, edge, adjustment: fn(&Rect) -> i32| -> i32 {
+                    let mut containers = vec![];
+                    node.containers_on_edge(edge, &mut containers);
+
+                    containers
+                        .iter()
+                        .filter_map(|idx| resize_dimensions.get(*idx).copied().flatten())
+                        .map(|resize| adjustment(&resize))
+                        .sum()
+                };
+
+                let (first_area, second_area) = match split {
+                    Split::Horizontal => {
+                        let delta = delta(first, OperationDirection::Right, |r| r.right)
+                            + delta(second, OperationDirection::Left, |r| r.left);
+                        let width = (area.right / 2 + delta).clamp(0, area.right);
+
+                        (
+                            Rect {
+                                right: width,
+                                ..*area
+                            },
+                            Rect {
+                                left: area.left + width,
+                                right: area.right - width,
+                                ..*area
+                            },
+                        )
+                    }
+                    Split::Vertical => {
+                        let delta = delta(first, OperationDirection::Down, |r| r.bottom)
+                            + delta(second, OperationDirection::Up, |r| r.top);
+                        let height = (area.bottom / 2 + delta).clamp(0, area.bottom);
+
+                        (
+                            Rect {
+                                bottom: height,
+                                ..*area
+                            },
+                            Rect {
+                                top: area.top + height,
+                                bottom: area.bottom - height,
+                                ..*area
+                            },
+                        )
+                    }
+                };
+
+                first.layout(&first_area, resize_dimensions, layouts);
+                second.layout(&second_area, resize_dimensions, layouts);
+            }
+        }
+    }
+}
+
+#[must_use]
+pub fn bsp(
+    area: &Rect,
+    len: usize,
+    bsp_strategy: BspStrategy,
+    layout_flip: Option<Axis>,
+    resize_dimensions: &[Option<Rect>],
+) -> Vec<Rect> {
+    if len == 0 {
+        return vec![];
+    }
+
+    let mut layouts = vec![*area; len];
+    BspNode::new(len, bsp_strategy).layout(area, resize_dimensions, &mut layouts);
+    flip_layouts(&mut layouts, area, layout_flip);
+
+    layouts
+}
+
+/// Finds the container in a direction from the positions of the containers in an unresized,
+/// unflipped layout, for strategies which can't be navigated with index arithmetic
+#[must_use]
+pub fn bsp_neighbour(
+    op_direction: OperationDirection,
+    idx: usize,
+    count: usize,
+    bsp_strategy: BspStrategy,
+) -> Option<usize> {
+    let layouts = bsp(&NAVIGATION_AREA, count, bsp_strategy, None, &[]);
+
+    Geometric {
+        layouts: &layouts,
+        focus_history: &[],
+    }
+    .neighbour(op_direction, idx)
+}
@@ -0,0 +1,153 @@
+use crate::arrangement::flip_layouts;
+use crate::arrangement::resize_rows;
+use crate::arrangement::rows;
+use crate::direction::Geometric;
+use crate::direction::NAVIGATION_AREA;
+use crate::Axis;
+use crate::LayoutOptions;
+use crate::OperationDirection;
+use crate::Rect;
+use crate::StackPlacement;
+
+/// The containers in the left stack, the center column and the right stack
+fn column_containers(
+    len: usize,
+    layout_options: LayoutOptions,
+) -> (Vec<usize>, Vec<usize>, Vec<usize>) {
+    let masters = layout_options.masters(len);
+    let main = (0..masters).collect();
+
+    let (left, right) = match layout_options.stack_placement {
+        StackPlacement::Alternate => {
+            (masters..len).partition(|idx| (idx - masters).is_multiple_of(2))
+        }
+        StackPlacement::FillLeft => {
+            let split = masters + (len - masters).div_ceil(2);
+            ((masters..split).collect(), (split..len).collect())
+        }
+    };
+
+    (left, main, right)
+}
+
+#[must_use]
+pub fn centered_master(
+    area: &Rect,
+    len: usize,
+    layout_flip: Option<Axis>,
+    layout_options: LayoutOptions,
+    resize_dimensions: &[Option<Rect>],
+) -> Vec<Rect> {
+    if len == 0 {
+        return vec![];
+    }
+
+    // A lone container can be kept in the middle of the work area instead of filling it
+    if let (1, Some(width)) = (len, layout_options.single_container_width) {
+        let width = width.clamp(0, area.right);
+
+        return vec![Rect {
+            left: area.left + (area.right - width) / 2,
+            right: width,
+            ..*area
+        }];
+    }
+
+    let (left, main, right) = column_containers(len, layout_options);
+
+    let main_width = if left.is_empty() && right.is_empty() {
+        area.right
+    } else {
+        layout_options.main_size(area.right)
+    };
+
+    // With a stack on only one side, that stack takes up all of the space left by the center
+    let left_width = match (left.is_empty(), right.is_empty()) {
+        (true, _) => 0,
+        (false, true) => area.right - main_width,
+        (false, false) => (area.right - main_width) / 2,
+    };
+
+    // The edges on either side of the center column are moved by the resize adjustments of
+    // every container touching them
+    let delta = |containers: &[usize], adjustment: fn(&Rect) -> i32| -> i32 {
+        containers
+            .iter()
+            .filter_map(|idx| resize_dimensions.get(*idx).copied().flatten())
+            .map(|resize| adjustment(&resize))
+            .sum()
+    };
+
+    let left_delta = if left.is_empty() {
+        0
+    } else {
+        delta(&left, |r| r.right) + delta(&main, |r| r.left)
+    };
+
+    let right_delta = if right.is_empty() {
+        0
+    } else {
+        delta(&main, |r| r.right) + delta(&right, |r| r.left)
+    };
+
+    let area_right = area.left + area.right;
+    let main_left = (area.left + left_width + left_delta).clamp(area.left, area_right);
+    let main_right =
+        (area.left + left_width + main_width + right_delta).clamp(main_left, area_right);
+
+    let columns = [
+        (area.left, main_left, left),
+        (main_left, main_right, main),
+        (main_right, area_right, right),
+    ];
+
+    let mut layouts = vec![Rect::default(); len];
+
+    for (column_left, column_right, containers) in columns {
+        if containers.is_empty() {
+            continue;
+        }
+
+        let mut column_layouts = rows(
+            &Rect {
+                left: column_left,
+                right: column_right - column_left,
+                ..*area
+            },
+            containers.len(),
+        );
+
+        let column_resize = containers
+            .iter()
+            .map(|idx| resize_dimensions.get(*idx).copied().flatten())
+            .collect::<Vec<_>>();
+
+        resize_rows(&mut column_layouts, &column_resize);
+
+        for (idx, layout) in containers.into_iter().zip(column_layouts) {
+            layouts[idx] = layout;
+        }
+    }
+
+    flip_layouts(&mut layouts, area, layout_flip);
+
+    layouts
+}
+
+/// Finds the container in a direction from the positions of the containers in an unresized,
+/// unflipped layout, as stack containers alternate between sides
+#[must_use]
+pub fn centered_master_neighbour(
+    op_direction: OperationDirection,
+    idx: usize,
+    count: usize,
+    layout_options: LayoutOptions,
+) -> Option<usize> {
+    let layouts = centered_master(&NAVIGATION_AREA, count, None, layout_options, &[]);
+
+    Geometric {
+        layouts: &layouts,
+        focus_history: &[],
+    }
+    .neighbour(op_direction, idx)
+}
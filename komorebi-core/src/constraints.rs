@@ -0,0 +1,275 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::Padding;
+use crate::Rect;
+
+/// Size constraints for the window in a container, which are honoured after a layout has been
+/// calculated by taking space from the containers that share an edge with it
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ContainerConstraints {
+    /// The minimum width of the window in pixels
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_width: Option<i32>,
+    /// The minimum height of the window in pixels
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_height: Option<i32>,
+    /// The ratio of width to height that the window is kept at, such as 1.7778 for 16:9
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aspect_ratio: Option<f32>,
+    /// The exact width of the window in pixels
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fixed_width: Option<i32>,
+}
+
+/// The smallest size in pixels that a window is shrunk to when giving space to its siblings
+const MINIMUM_SIZE: i32 = 100;
+
+/// The range of sizes that a container can be given along one axis, including its padding
+#[derive(Clone, Copy)]
+struct Bounds {
+    min: i32,
+    max: i32,
+}
+
+impl Bounds {
+    /// Containers without a constraint can give space to their siblings until they reach the
+    /// minimum size, but are never grown to reach it
+    fn new(size: i32, padding: i32, min: Option<i32>, max: Option<i32>) -> Self {
+        let min = min
+            .map_or(0, |min| min + padding)
+            .max((MINIMUM_SIZE + padding).min(size));
+        let max = max.map_or(i32::MAX, |max| (max + padding).max(min));
+
+        Self { min, max }
+    }
+}
+
+impl ContainerConstraints {
+    /// The exact width that the window should have, where a fixed width takes precedence over
+    /// the width that would match the aspect ratio at its current height
+    #[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+    fn target_width(&self, height: i32) -> Option<i32> {
+        self.fixed_width.or_else(|| {
+            self.aspect_ratio
+                .map(|ratio| (height.max(0) as f32 * ratio).round() as i32)
+        })
+    }
+}
+
+/// Resizes the containers of an unpadded layout to honour their constraints, by moving the edges
+/// they share with siblings as far as the constraints of those siblings allow
+///
+/// The layout is padded afterwards, and windows with an aspect ratio are letterboxed to fit it
+pub fn apply_constraints(
+    layouts: &mut [Rect],
+    constraints: &[Option<ContainerConstraints>],
+    container_padding: Option<Padding>,
+) {
+    let padding = container_padding.unwrap_or_default();
+    let constraint = |idx: usize| constraints.get(idx).copied().flatten();
+
+    let vertical = padding.top + padding.bottom;
+    let horizontal = padding.left + padding.right;
+
+    // Heights are resolved first so that widths which depend on an aspect ratio are calculated
+    // from the final height of each container
+    let height_bounds = layouts
+        .iter()
+        .enumerate()
+        .map(|(idx, layout)| {
+            let min_height = constraint(idx).and_then(|c| c.min_height);
+            Bounds::new(layout.bottom, vertical, min_height, None)
+        })
+        .collect::<Vec<_>>();
+
+    let mut transposed = layouts.iter().map(Rect::transposed).collect::<Vec<_>>();
+    resolve_widths(&mut transposed, &height_bounds);
+
+    for (layout, rect) in layouts.iter_mut().zip(&transposed) {
+        *layout = rect.transposed();
+    }
+
+    // A fixed width is both a minimum and a maximum, whereas a container with an aspect ratio is
+    // only ever narrowed to match it, as its window is letterboxed when there isn't enough room
+    let width_bounds = layouts
+        .iter()
+        .enumerate()
+        .map(|(idx, layout)| {
+            let (min_width, max_width) = constraint(idx).map_or((None, None), |c| {
+                (
+                    c.min_width.max(c.fixed_width),
+                    c.target_width(layout.bottom - vertical),
+                )
+            });
+
+            Bounds::new(layout.right, horizontal, min_width, max_width)
+        })
+        .collect::<Vec<_>>();
+
+    resolve_widths(layouts, &width_bounds);
+
+    for (idx, layout) in layouts.iter_mut().enumerate() {
+        layout.add_padding(container_padding);
+
+        if let Some(ratio) = constraint(idx).and_then(|c| c.aspect_ratio) {
+            letterbox(layout, ratio);
+        }
+    }
+}
+
+#[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+fn letterbox(layout: &mut Rect, ratio: f32) {
+    if ratio <= 0.0 || layout.bottom <= 0 {
+        return;
+    }
+
+    if layout.right as f32 / layout.bottom as f32 > ratio {
+        let width = (layout.bottom as f32 * ratio).round() as i32;
+        layout.constrain(Option::from(width), None);
+    } else {
+        let height = (layout.right as f32 / ratio).round() as i32;
+        layout.constrain(None, Option::from(height));
+    }
+}
+
+fn resolve_widths(layouts: &mut [Rect], bounds: &[Bounds]) {
+    // Moving an edge never takes a container outside of its bounds, so every pass can only
+    // satisfy more constraints, and the passes stop as soon as nothing else can be moved
+    for _ in 0..layouts.len() {
+        let mut moved = false;
+
+        for (idx, bound) in bounds.iter().enumerate() {
+            let width = layouts[idx].right;
+
+            let change = if width < bound.min {
+                bound.min - width
+            } else if width > bound.max {
+                bound.max - width
+            } else {
+                continue;
+            };
+
+            moved |= resize(layouts, bounds, idx, change);
+        }
+
+        if !moved {
+            break;
+        }
+    }
+}
+
+/// Grows a container by a positive change or shrinks it by a negative one, first from its right
+/// edge and then from its left edge, returning whether either edge was moved
+fn resize(layouts: &mut [Rect], bounds: &[Bounds], idx: usize, mut change: i32) -> bool {
+    let mut moved = false;
+
+    for right_edge in [true, false] {
+        if change == 0 {
+            break;
+        }
+
+        let rect = layouts[idx];
+        let x = if right_edge {
+            rect.left + rect.right
+        } else {
+            rect.left
+        };
+
+        // Growing moves the right edge to the right and the left edge to the left
+        let direction = if (change > 0) == right_edge { 1 } else { -1 };
+        let (before, after) = edge_containers(layouts, x, rect.top, rect.top + rect.bottom);
+        let delta = change
+            .abs()
+            .min(slack(layouts, bounds, &before, &after, direction));
+
+        if delta > 0 {
+            for &i in &before {
+                layouts[i].right += direction * delta;
+            }
+
+            for &i in &after {
+                layouts[i].left += direction * delta;
+                layouts[i].right -= direction * delta;
+            }
+
+            change -= change.signum() * delta;
+            moved = true;
+        }
+    }
+
+    moved
+}
+
+/// The containers whose right edge (before) or left edge (after) lies on a vertical edge at `x`,
+/// following every container that overlaps vertically with the part of the edge found so far
+fn edge_containers(
+    layouts: &[Rect],
+    x: i32,
+    mut top: i32,
+    mut bottom: i32,
+) -> (Vec<usize>, Vec<usize>) {
+    loop {
+        let mut before = vec![];
+        let mut after = vec![];
+
+        for (idx, layout) in layouts.iter().enumerate() {
+            let overlaps = layout.top < bottom && top < layout.top + layout.bottom;
+
+            if overlaps && layout.left + layout.right == x {
+                before.push(idx);
+            } else if overlaps && layout.left == x {
+                after.push(idx);
+            }
+        }
+
+        let extent = before
+            .iter()
+            .chain(&after)
+            .fold((top, bottom), |(t, b), idx| {
+                let layout = &layouts[*idx];
+                (t.min(layout.top), b.max(layout.top + layout.bottom))
+            });
+
+        if extent == (top, bottom) {
+            return (before, after);
+        }
+
+        (top, bottom) = extent;
+    }
+}
+
+/// How far an edge can be moved in a direction before a container on either side of it would
+/// leave its bounds, which is nothing for an edge on the boundary of the layout
+fn slack(
+    layouts: &[Rect],
+    bounds: &[Bounds],
+    before: &[usize],
+    after: &[usize],
+    direction: i32,
+) -> i32 {
+    if before.is_empty() || after.is_empty() {
+        return 0;
+    }
+
+    let (growing, shrinking) = if direction > 0 {
+        (before, after)
+    } else {
+        (after, before)
+    };
+
+    let can_grow = growing
+        .iter()
+        .map(|idx| bounds[*idx].max.saturating_sub(layouts[*idx].right))
+        .min()
+        .unwrap_or(0);
+
+    let can_shrink = shrinking
+        .iter()
+        .map(|idx| layouts[*idx].right - bounds[*idx].min)
+        .min()
+        .unwrap_or(0);
+
+    can_grow.min(can_shrink).max(0)
+}
@@ -3,134 +3,457 @@ use std::fs::File;
 use std::io::BufReader;
 use std::ops::Deref;
 use std::ops::DerefMut;
+use std::ops::Range;
 use std::path::Path;
 
 use color_eyre::eyre::anyhow;
-use color_eyre::eyre::bail;
 use color_eyre::Result;
 use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
 use serde::Deserialize;
 use serde::Serialize;
 
+use crate::layout_validation::ensure_valid;
+use crate::layout_validation::LayoutError;
+use crate::layout_validation::LayoutErrorKind;
 use crate::Rect;
+use crate::Sizing;
 
 #[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
-pub struct CustomLayout(Vec<Column>);
+#[serde(from = "CustomLayoutDefinition")]
+pub struct CustomLayout {
+    /// Whether the columns are laid out from left to right or as rows from top to bottom
+    orientation: Orientation,
+    columns: Vec<ColumnDefinition>,
+    /// The widths of the columns as they were loaded, which resized columns can be reset to
+    #[serde(skip)]
+    original_widths: Vec<Option<ColumnWidth>>,
+}
+
+/// Layout files can either be a list of columns or specify an orientation for the columns
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum CustomLayoutDefinition {
+    Columns(Vec<ColumnDefinition>),
+    WithOrientation(OrientedColumns),
+}
+
+#[derive(Deserialize)]
+struct OrientedColumns {
+    #[serde(default)]
+    orientation: Orientation,
+    columns: Vec<ColumnDefinition>,
+}
+
+impl From<CustomLayoutDefinition> for CustomLayout {
+    fn from(definition: CustomLayoutDefinition) -> Self {
+        let (orientation, mut columns) = match definition {
+            CustomLayoutDefinition::Columns(columns) => (Orientation::default(), columns),
+            CustomLayoutDefinition::WithOrientation(OrientedColumns {
+                orientation,
+                columns,
+            }) => (orientation, columns),
+        };
+
+        // Older layouts could only configure a width on the primary column
+        for definition in &mut columns {
+            if let Column::Primary(Some(width)) = definition.column {
+                definition.column = Column::Primary(None);
+                definition.width = definition.width.or(Some(width));
+            }
+        }
+
+        Self {
+            orientation,
+            original_widths: columns.iter().map(|definition| definition.width).collect(),
+            columns,
+        }
+    }
+}
 
 impl Deref for CustomLayout {
-    type Target = Vec<Column>;
+    type Target = Vec<ColumnDefinition>;
 
     fn deref(&self) -> &Self::Target {
-        &self.0
+        &self.columns
     }
 }
 
 impl DerefMut for CustomLayout {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.0
+        &mut self.columns
     }
 }
 
 impl CustomLayout {
     pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self> {
-        let path = path.as_ref();
-        let layout: Self = match path.extension() {
-            Some(extension) if extension == "yaml" || extension == "yml" => {
-                serde_json::from_reader(BufReader::new(File::open(path)?))?
-            }
-            Some(extension) if extension == "json" => {
-                serde_json::from_reader(BufReader::new(File::open(path)?))?
-            }
-            _ => return Err(anyhow!("custom layouts must be json or yaml files")),
-        };
-
-        if !layout.is_valid() {
-            bail!("the layout file provided was invalid");
-        }
+        let layout: Self = deserialize_file(path.as_ref())?;
+        ensure_valid(&layout.validate())?;
 
         Ok(layout)
     }
 
+    /// Reads a layout file as whichever format its shape suggests rather than trying each format
+    /// in turn, so that a mistake in the file is reported instead of a mismatch with every format
+    pub(crate) fn from_path_with_shape(path: &Path, is_list: bool) -> Result<Self> {
+        Ok(Self::from(if is_list {
+            CustomLayoutDefinition::Columns(deserialize_file(path)?)
+        } else {
+            CustomLayoutDefinition::WithOrientation(deserialize_file(path)?)
+        }))
+    }
+
+    #[must_use]
+    pub const fn orientation(&self) -> Orientation {
+        self.orientation
+    }
+
+    /// The equivalent column-oriented layout of a row-oriented layout, to be used in a
+    /// transposed area where rows become columns and splits swap direction
+    #[must_use]
+    pub fn transposed(&self) -> Self {
+        let columns = self
+            .iter()
+            .map(|definition| ColumnDefinition {
+                column: definition.column.transposed(),
+                width: definition.width,
+            })
+            .collect();
+
+        Self {
+            orientation: Orientation::Columns,
+            columns,
+            original_widths: self.original_widths.clone(),
+        }
+    }
+
     #[must_use]
     pub fn column_with_idx(&self, idx: usize) -> (usize, Option<&Column>) {
         let column_idx = self.column_for_container_idx(idx);
-        let column = self.get(column_idx);
+        let column = self.get(column_idx).map(|definition| &definition.column);
         (column_idx, column)
     }
 
     #[must_use]
     pub fn primary_idx(&self) -> Option<usize> {
-        for (i, column) in self.iter().enumerate() {
-            if let Column::Primary(_) = column {
-                return Option::from(i);
-            }
+        self.iter()
+            .position(|definition| matches!(definition.column, Column::Primary(_)))
+    }
+
+    #[must_use]
+    pub fn primary_width_percentage(&self) -> Option<f32> {
+        match self.column_width(self.primary_idx()?) {
+            Some(ColumnWidth::WidthPercentage(percentage)) => Option::from(percentage),
+            _ => None,
         }
+    }
 
-        None
+    pub fn set_primary_width_percentage(&mut self, percentage: f32) {
+        if let Some(primary_idx) = self.primary_idx() {
+            self.set_column_width(
+                primary_idx,
+                Option::from(ColumnWidth::WidthPercentage(percentage)),
+            );
+        }
     }
 
     #[must_use]
-    pub fn primary_width_percentage(&self) -> Option<f32> {
-        for column in self.iter() {
-            if let Column::Primary(Option::Some(ColumnWidth::WidthPercentage(percentage))) = column
-            {
-                return Option::from(*percentage);
+    pub fn column_width(&self, column_idx: usize) -> Option<ColumnWidth> {
+        self.get(column_idx).and_then(|definition| definition.width)
+    }
+
+    pub fn set_column_width(&mut self, column_idx: usize, width: Option<ColumnWidth>) {
+        if let Some(definition) = self.get_mut(column_idx) {
+            definition.width = width;
+        }
+    }
+
+    /// Moves the boundaries of a column by adjusting its width; columns without a configured
+    /// width start from an equal share of the area, and a column never grows into the space that
+    /// the other columns need in an area of the given length
+    pub fn adjust_column_width(
+        &mut self,
+        column_idx: usize,
+        sizing: Sizing,
+        percentage: f32,
+        pixels: i32,
+        total: i32,
+    ) {
+        let previous = self.column_width(column_idx);
+
+        // Every other column keeps its configured width, and every column without one keeps at
+        // least the size of one adjustment
+        let mut needed = 0;
+        for (idx, definition) in self.iter().enumerate() {
+            if idx != column_idx {
+                needed += definition.width.map_or_else(
+                    || ColumnWidth::WidthPercentage(percentage).pixels(total),
+                    |width| width.pixels(total),
+                );
+            }
+        }
+
+        let available = total - needed;
+
+        #[allow(clippy::cast_precision_loss)]
+        let equal_share = 100.0 / self.len() as f32;
+
+        let width = match previous {
+            Some(ColumnWidth::WidthPixels(width)) => ColumnWidth::WidthPixels(
+                match sizing {
+                    Sizing::Increase => width + pixels,
+                    Sizing::Decrease => width - pixels,
+                }
+                .min(available)
+                .max(pixels),
+            ),
+            width => {
+                let current = match width {
+                    Some(ColumnWidth::WidthPercentage(width)) => width,
+                    _ => equal_share,
+                };
+
+                #[allow(clippy::cast_precision_loss)]
+                let maximum = if total > 0 {
+                    available as f32 * 100.0 / total as f32
+                } else {
+                    0.0
+                };
+
+                ColumnWidth::WidthPercentage(
+                    match sizing {
+                        Sizing::Increase => current + percentage,
+                        Sizing::Decrease => current - percentage,
+                    }
+                    .min(maximum)
+                    .min(100.0 - percentage)
+                    .max(percentage),
+                )
             }
+        };
+
+        self.set_column_width(column_idx, Option::from(width));
+
+        // An adjustment which would leave the layout invalid is not applied at all
+        if !self.is_valid() {
+            self.set_column_width(column_idx, previous);
         }
+    }
 
-        None
+    /// Puts the width of every column back to the width that it was loaded with
+    pub fn reset_column_widths(&mut self) {
+        for (definition, width) in self.columns.iter_mut().zip(&self.original_widths) {
+            definition.width = *width;
+        }
     }
 
-    pub fn set_primary_width_percentage(&mut self, percentage: f32) {
-        for column in self.iter_mut() {
-            if let Column::Primary(Option::Some(ColumnWidth::WidthPercentage(current))) = column {
-                *current = percentage;
+    /// The widths of the first `active` columns in an area of the given width, where columns
+    /// without a configured width share whatever space remains equally
+    #[must_use]
+    pub fn column_widths(&self, total: i32, active: usize) -> Vec<i32> {
+        let active = active.min(self.len());
+        let mut configured = vec![];
+        let mut used = 0;
+
+        // Configured widths are capped so that every active column after them still gets at
+        // least one pixel
+        #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+        for (idx, definition) in self.iter().take(active).enumerate() {
+            let width = definition.width.map(|width| {
+                let remaining = (active - idx - 1) as i32;
+                let width = width.pixels(total).min(total - used - remaining).max(1);
+                used += width;
+
+                width
+            });
+
+            configured.push(width);
+        }
+
+        let unconfigured = configured.iter().filter(|width| width.is_none()).count();
+
+        #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+        let equal_share = if unconfigured == 0 {
+            0
+        } else {
+            ((total - used) / unconfigured as i32).max(1)
+        };
+
+        let mut widths = configured
+            .iter()
+            .map(|width| width.unwrap_or(equal_share))
+            .collect::<Vec<_>>();
+
+        // If every column has a configured width, the last one fills any space that remains
+        if unconfigured == 0 {
+            if let Some(last) = widths.last_mut() {
+                *last = (*last + total - used).max(1);
             }
         }
+
+        widths
     }
 
     #[must_use]
     pub fn is_valid(&self) -> bool {
+        self.validate().is_empty()
+    }
+
+    /// Every problem which makes this layout invalid, with the column that each was found in
+    #[must_use]
+    pub fn validate(&self) -> Vec<LayoutError> {
+        let mut errors = vec![];
+
         // A valid layout must have at least one column
         if self.is_empty() {
-            return false;
-        };
+            errors.push(LayoutError::new("columns", LayoutErrorKind::NoColumns));
+            return errors;
+        }
 
-        // Vertical column splits aren't supported at the moment
-        for column in self.iter() {
-            match column {
-                Column::Tertiary(ColumnSplit::Vertical)
-                | Column::Secondary(Some(ColumnSplitWithCapacity::Vertical(_))) => return false,
-                _ => {}
+        let location = |idx: usize| format!("columns[{idx}]");
+        let mut primaries = 0;
+        let mut tertiaries = 0;
+
+        // There must only be one primary and one tertiary column
+        for (idx, definition) in self.iter().enumerate() {
+            match definition.column {
+                Column::Primary(_) => {
+                    primaries += 1;
+                    if primaries > 1 {
+                        errors.push(LayoutError::new(
+                            location(idx),
+                            LayoutErrorKind::MultiplePrimaries,
+                        ));
+                    }
+                }
+                Column::Tertiary(_) => {
+                    tertiaries += 1;
+                    if tertiaries > 1 {
+                        errors.push(LayoutError::new(
+                            location(idx),
+                            LayoutErrorKind::MultipleTertiaries,
+                        ));
+                    }
+                }
+                Column::Secondary(Some(
+                    ColumnSplitWithCapacity::Horizontal(0) | ColumnSplitWithCapacity::Vertical(0),
+                )) => {
+                    errors.push(LayoutError::new(
+                        location(idx),
+                        LayoutErrorKind::UnsupportedSplit,
+                    ));
+                }
+                Column::Secondary(_) => {}
             }
         }
 
+        if primaries == 0 {
+            errors.push(LayoutError::new("columns", LayoutErrorKind::MissingPrimary));
+        }
+
+        if tertiaries == 0 {
+            errors.push(LayoutError::new(
+                "columns",
+                LayoutErrorKind::MissingTertiary,
+            ));
+        }
+
         // The final column must not have a fixed capacity
-        match self.last() {
-            Some(Column::Tertiary(_)) => {}
-            _ => return false,
+        if tertiaries > 0 && !matches!(self[self.len() - 1].column, Column::Tertiary(_)) {
+            errors.push(LayoutError::new(
+                location(self.len() - 1),
+                LayoutErrorKind::FinalColumnNotTertiary,
+            ));
         }
 
-        let mut primaries = 0;
-        let mut tertiaries = 0;
+        let mut total_percentage = 0.0;
+        let mut unconfigured = 0;
+
+        for (idx, definition) in self.iter().enumerate() {
+            match definition.width {
+                Some(ColumnWidth::WidthPercentage(percentage)) => {
+                    // Every percentage must leave some space for the other columns
+                    if percentage <= 0.0 || percentage >= 100.0 {
+                        errors.push(LayoutError::new(
+                            format!("{}.width", location(idx)),
+                            LayoutErrorKind::BadPercentage(percentage),
+                        ));
+                    }
+
+                    total_percentage += percentage;
+                }
+                Some(ColumnWidth::WidthPixels(pixels)) => {
+                    if pixels <= 0 {
+                        errors.push(LayoutError::new(
+                            format!("{}.width", location(idx)),
+                            LayoutErrorKind::BadPixels(pixels),
+                        ));
+                    }
+                }
+                None => unconfigured += 1,
+            }
+        }
 
-        for column in self.iter() {
-            match column {
-                Column::Primary(_) => primaries += 1,
-                Column::Tertiary(_) => tertiaries += 1,
-                Column::Secondary(_) => {}
+        // Percentages can't add up to more than the whole area, and if there are columns without
+        // a configured width then there must be some space left for them
+        let percentages_fit = if unconfigured == 0 {
+            total_percentage <= 100.0
+        } else {
+            total_percentage < 100.0
+        };
+
+        if !percentages_fit {
+            errors.push(LayoutError::new(
+                "columns",
+                LayoutErrorKind::PercentagesExceedArea(total_percentage),
+            ));
+        }
+
+        errors
+    }
+
+    /// The containers laid out in each active column for a given number of containers, along
+    /// with the direction that the containers within each column are split in
+    #[must_use]
+    pub fn column_containers(&self, len: usize) -> Vec<(Range<usize>, ColumnSplit)> {
+        // With fewer containers than columns, every container gets an equal width column
+        if len < self.len() {
+            return (0..len)
+                .map(|idx| (idx..idx + 1, ColumnSplit::Horizontal))
+                .collect();
+        }
+
+        let mut columns = vec![];
+        let mut start = 0;
+
+        for definition in self.iter() {
+            let (count, split) = match definition.column {
+                Column::Primary(_) | Column::Secondary(None) => (1, ColumnSplit::Horizontal),
+                Column::Secondary(Some(ColumnSplitWithCapacity::Horizontal(capacity))) => {
+                    (capacity, ColumnSplit::Horizontal)
+                }
+                Column::Secondary(Some(ColumnSplitWithCapacity::Vertical(capacity))) => {
+                    (capacity, ColumnSplit::Vertical)
+                }
+                Column::Tertiary(split) => (len.saturating_sub(start), split),
+            };
+
+            let end = (start + count).min(len);
+            if end > start {
+                columns.push((start..end, split));
             }
+
+            start = end;
         }
 
-        // There must only be one primary and one tertiary column
-        matches!(primaries, 1) && matches!(tertiaries, 1)
+        columns
     }
 
     pub(crate) fn column_container_counts(&self) -> HashMap<usize, usize> {
         let mut count_map = HashMap::new();
 
-        for (idx, column) in self.iter().enumerate() {
-            match column {
+        for (idx, definition) in self.iter().enumerate() {
+            match definition.column {
                 Column::Primary(_) | Column::Secondary(None) => {
                     count_map.insert(idx, 1);
                 }
@@ -139,7 +462,7 @@ impl CustomLayout {
                         idx,
                         match split {
                             ColumnSplitWithCapacity::Vertical(n)
-                            | ColumnSplitWithCapacity::Horizontal(n) => *n,
+                            | ColumnSplitWithCapacity::Horizontal(n) => n,
                         },
                     );
                 }
@@ -209,45 +532,24 @@ impl CustomLayout {
             bottom: work_area.bottom,
         }
     }
+}
 
-    #[must_use]
-    pub fn column_area_with_last(
-        len: usize,
-        work_area: &Rect,
-        primary_right: i32,
-        last_column: Option<Rect>,
-        offset: Option<usize>,
-    ) -> Rect {
-        let divisor = offset.map_or_else(|| len - 1, |offset| len - offset - 1);
-
-        #[allow(clippy::cast_possible_wrap, clippy::cast_possible_truncation)]
-        let equal_width = (work_area.right - primary_right) / divisor as i32;
-        let left = last_column.map_or(work_area.left, |last| last.left + last.right);
-        let right = equal_width;
-
-        Rect {
-            left,
-            top: work_area.top,
-            right,
-            bottom: work_area.bottom,
-        }
-    }
-
-    #[must_use]
-    pub fn main_column_area(
-        work_area: &Rect,
-        primary_right: i32,
-        last_column: Option<Rect>,
-    ) -> Rect {
-        let left = last_column.map_or(work_area.left, |last| last.left + last.right);
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum Orientation {
+    /// Columns are laid out from left to right
+    #[default]
+    Columns,
+    /// Columns are laid out as rows from top to bottom
+    Rows,
+}
 
-        Rect {
-            left,
-            top: work_area.top,
-            right: primary_right,
-            bottom: work_area.bottom,
-        }
-    }
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ColumnDefinition {
+    #[serde(flatten)]
+    pub column: Column,
+    /// Width of the column, which shares the remaining space with the other columns if not set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub width: Option<ColumnWidth>,
 }
 
 #[derive(Clone, Copy, Debug, Serialize, Deserialize, JsonSchema)]
@@ -258,9 +560,87 @@ pub enum Column {
     Tertiary(ColumnSplit),
 }
 
+impl Column {
+    /// Swaps the direction of splits for columns calculated in a transposed area
+    #[must_use]
+    pub const fn transposed(self) -> Self {
+        match self {
+            Self::Primary(width) => Self::Primary(width),
+            Self::Secondary(Some(ColumnSplitWithCapacity::Horizontal(capacity))) => {
+                Self::Secondary(Some(ColumnSplitWithCapacity::Vertical(capacity)))
+            }
+            Self::Secondary(Some(ColumnSplitWithCapacity::Vertical(capacity))) => {
+                Self::Secondary(Some(ColumnSplitWithCapacity::Horizontal(capacity)))
+            }
+            Self::Secondary(None) => Self::Secondary(None),
+            Self::Tertiary(ColumnSplit::Horizontal) => Self::Tertiary(ColumnSplit::Vertical),
+            Self::Tertiary(ColumnSplit::Vertical) => Self::Tertiary(ColumnSplit::Horizontal),
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, Serialize, Deserialize, JsonSchema)]
 pub enum ColumnWidth {
     WidthPercentage(f32),
+    WidthPixels(i32),
+}
+
+impl ColumnWidth {
+    #[must_use]
+    #[allow(
+        clippy::cast_precision_loss,
+        clippy::cast_possible_truncation,
+        clippy::cast_possible_wrap
+    )]
+    pub fn pixels(self, total: i32) -> i32 {
+        match self {
+            Self::WidthPercentage(percentage) => (total as f32 * percentage / 100.0) as i32,
+            Self::WidthPixels(pixels) => pixels,
+        }
+    }
+}
+
+/// Reads a custom layout file in any of the supported formats
+pub(crate) fn deserialize_file<T: DeserializeOwned>(path: &Path) -> Result<T> {
+    Ok(match path.extension() {
+        // YAML files are converted to the same structure as JSON files first, so that enums can
+        // be written either as maps or with YAML tags
+        Some(extension) if extension == "yaml" || extension == "yml" => {
+            let value: serde_yaml::Value =
+                serde_yaml::from_reader(BufReader::new(File::open(path)?))?;
+            serde_json::from_value(serde_json::to_value(untag(value))?)?
+        }
+        Some(extension) if extension == "json" => {
+            serde_json::from_reader(BufReader::new(File::open(path)?))?
+        }
+        _ => return Err(anyhow!("custom layouts must be json or yaml files")),
+    })
+}
+
+/// Replaces YAML tags such as `!Horizontal 2` with the single key maps used for enums in JSON
+fn untag(value: serde_yaml::Value) -> serde_yaml::Value {
+    match value {
+        serde_yaml::Value::Tagged(tagged) => {
+            let tag = tagged.tag.to_string();
+            let mut mapping = serde_yaml::Mapping::new();
+            mapping.insert(
+                serde_yaml::Value::String(tag.trim_start_matches('!').to_string()),
+                untag(tagged.value),
+            );
+
+            serde_yaml::Value::Mapping(mapping)
+        }
+        serde_yaml::Value::Sequence(sequence) => {
+            serde_yaml::Value::Sequence(sequence.into_iter().map(untag).collect())
+        }
+        serde_yaml::Value::Mapping(mapping) => serde_yaml::Value::Mapping(
+            mapping
+                .into_iter()
+                .map(|(key, value)| (key, untag(value)))
+                .collect(),
+        ),
+        value => value,
+    }
 }
 
 #[derive(Clone, Copy, Debug, Serialize, Deserialize, JsonSchema)]
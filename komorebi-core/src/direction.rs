@@ -1,9 +1,56 @@
+use clap::ValueEnum;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use strum::Display;
+use strum::EnumString;
+
+use crate::arrangement::grid_dimensions;
+use crate::bsp::bsp_neighbour;
+use crate::centered_master::centered_master_neighbour;
 use crate::custom_layout::Column;
 use crate::custom_layout::ColumnSplit;
 use crate::custom_layout::ColumnSplitWithCapacity;
 use crate::custom_layout::CustomLayout;
+use crate::custom_layout::Orientation;
+use crate::BspStrategy;
 use crate::DefaultLayout;
+use crate::LayoutOptions;
 use crate::OperationDirection;
+use crate::Rect;
+use crate::TreeLayout;
+
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    Display,
+    EnumString,
+    ValueEnum,
+    JsonSchema,
+)]
+#[strum(serialize_all = "snake_case")]
+pub enum DirectionStrategy {
+    /// Find the container in a direction using the index arithmetic of the layout
+    #[default]
+    Layout,
+    /// Find the container in a direction using the calculated positions of containers on screen
+    Geometric,
+}
+
+/// The area that layouts are calculated in when navigating them geometrically, large enough for
+/// every container to have a non-zero size in deeply nested splits
+pub(crate) const NAVIGATION_AREA: Rect = Rect {
+    left: 0,
+    top: 0,
+    right: 1 << 24,
+    bottom: 1 << 24,
+};
 
 pub trait Direction {
     fn index_in_direction(
@@ -11,6 +58,7 @@ pub trait Direction {
         op_direction: OperationDirection,
         idx: usize,
         count: usize,
+        layout_options: LayoutOptions,
     ) -> Option<usize>;
 
     fn is_valid_direction(
@@ -18,11 +66,12 @@ pub trait Direction {
         op_direction: OperationDirection,
         idx: usize,
         count: usize,
+        layout_options: LayoutOptions,
     ) -> bool;
-    fn up_index(&self, idx: usize) -> usize;
-    fn down_index(&self, idx: usize) -> usize;
-    fn left_index(&self, idx: usize) -> usize;
-    fn right_index(&self, idx: usize) -> usize;
+    fn up_index(&self, idx: usize, count: usize, layout_options: LayoutOptions) -> usize;
+    fn down_index(&self, idx: usize, count: usize, layout_options: LayoutOptions) -> usize;
+    fn left_index(&self, idx: usize, count: usize, layout_options: LayoutOptions) -> usize;
+    fn right_index(&self, idx: usize, count: usize, layout_options: LayoutOptions) -> usize;
 }
 
 impl Direction for DefaultLayout {
@@ -31,32 +80,33 @@ impl Direction for DefaultLayout {
         op_direction: OperationDirection,
         idx: usize,
         count: usize,
+        layout_options: LayoutOptions,
     ) -> Option<usize> {
         match op_direction {
             OperationDirection::Left => {
-                if self.is_valid_direction(op_direction, idx, count) {
-                    Option::from(self.left_index(idx))
+                if self.is_valid_direction(op_direction, idx, count, layout_options) {
+                    Option::from(self.left_index(idx, count, layout_options))
                 } else {
                     None
                 }
             }
             OperationDirection::Right => {
-                if self.is_valid_direction(op_direction, idx, count) {
-                    Option::from(self.right_index(idx))
+                if self.is_valid_direction(op_direction, idx, count, layout_options) {
+                    Option::from(self.right_index(idx, count, layout_options))
                 } else {
                     None
                 }
             }
             OperationDirection::Up => {
-                if self.is_valid_direction(op_direction, idx, count) {
-                    Option::from(self.up_index(idx))
+                if self.is_valid_direction(op_direction, idx, count, layout_options) {
+                    Option::from(self.up_index(idx, count, layout_options))
                 } else {
                     None
                 }
             }
             OperationDirection::Down => {
-                if self.is_valid_direction(op_direction, idx, count) {
-                    Option::from(self.down_index(idx))
+                if self.is_valid_direction(op_direction, idx, count, layout_options) {
+                    Option::from(self.down_index(idx, count, layout_options))
                 } else {
                     None
                 }
@@ -69,98 +119,198 @@ impl Direction for DefaultLayout {
         op_direction: OperationDirection,
         idx: usize,
         count: usize,
+        layout_options: LayoutOptions,
     ) -> bool {
+        if let (Self::BSP, BspStrategy::Spiral | BspStrategy::Balanced) =
+            (self, layout_options.bsp_strategy)
+        {
+            return bsp_neighbour(op_direction, idx, count, layout_options.bsp_strategy).is_some();
+        }
+
+        let masters = layout_options.masters(count);
+
         match op_direction {
             OperationDirection::Up => match self {
                 Self::BSP => count > 2 && idx != 0 && idx != 1,
-                Self::Columns => false,
-                Self::Rows | Self::HorizontalStack => idx != 0,
-                Self::VerticalStack => idx != 0 && idx != 1,
-                Self::UltrawideVerticalStack => idx > 2,
+                Self::Columns | Self::Scrolling => false,
+                Self::Rows => idx != 0,
+                Self::VerticalStack => idx != 0 && idx != masters,
+                Self::HorizontalStack => idx >= masters,
+                Self::UltrawideVerticalStack => (idx < masters && idx != 0) || idx > masters + 1,
+                Self::Grid => idx >= grid_dimensions(count).0,
+                Self::CenteredMaster => {
+                    centered_master_neighbour(op_direction, idx, count, layout_options).is_some()
+                }
             },
             OperationDirection::Down => match self {
                 Self::BSP => count > 2 && idx != count - 1 && idx % 2 != 0,
-                Self::Columns => false,
+                Self::Columns | Self::Scrolling => false,
                 Self::Rows => idx != count - 1,
-                Self::VerticalStack => idx != 0 && idx != count - 1,
-                Self::HorizontalStack => idx == 0,
-                Self::UltrawideVerticalStack => idx > 1 && idx != count - 1,
+                Self::VerticalStack => idx != count - 1 && idx + 1 != masters,
+                Self::HorizontalStack => idx < masters && count > masters,
+                Self::UltrawideVerticalStack => {
+                    idx + 1 < masters || (idx > masters && idx != count - 1)
+                }
+                Self::Grid => {
+                    let (columns, rows) = grid_dimensions(count);
+                    idx / columns < rows - 1
+                }
+                Self::CenteredMaster => {
+                    centered_master_neighbour(op_direction, idx, count, layout_options).is_some()
+                }
             },
             OperationDirection::Left => match self {
                 Self::BSP => count > 1 && idx != 0,
-                Self::Columns | Self::VerticalStack => idx != 0,
+                Self::Columns | Self::Scrolling => idx != 0,
                 Self::Rows => false,
-                Self::HorizontalStack => idx != 0 && idx != 1,
-                Self::UltrawideVerticalStack => count > 1 && idx != 1,
+                Self::VerticalStack => idx >= masters,
+                Self::HorizontalStack => idx != 0 && idx != masters,
+                Self::UltrawideVerticalStack => count > masters && idx != masters,
+                Self::Grid => !idx.is_multiple_of(grid_dimensions(count).0),
+                Self::CenteredMaster => {
+                    centered_master_neighbour(op_direction, idx, count, layout_options).is_some()
+                }
             },
             OperationDirection::Right => match self {
                 Self::BSP => count > 1 && idx % 2 == 0 && idx != count - 1,
-                Self::Columns => idx != count - 1,
+                Self::Columns | Self::Scrolling => idx != count - 1,
                 Self::Rows => false,
-                Self::VerticalStack => idx == 0,
-                Self::HorizontalStack => idx != 0 && idx != count - 1,
-                Self::UltrawideVerticalStack => match count {
-                    0 | 1 => false,
-                    2 => idx != 0,
-                    _ => idx < 2,
-                },
+                Self::VerticalStack => idx < masters && count > masters,
+                Self::HorizontalStack => idx != count - 1 && idx + 1 != masters,
+                Self::UltrawideVerticalStack => {
+                    idx == masters || (idx < masters && count > masters + 1)
+                }
+                Self::Grid => {
+                    let (columns, _) = grid_dimensions(count);
+                    idx != count - 1 && idx % columns != columns - 1
+                }
+                Self::CenteredMaster => {
+                    centered_master_neighbour(op_direction, idx, count, layout_options).is_some()
+                }
             },
         }
     }
 
-    fn up_index(&self, idx: usize) -> usize {
+    fn up_index(&self, idx: usize, count: usize, layout_options: LayoutOptions) -> usize {
         match self {
-            Self::BSP => {
-                if idx % 2 == 0 {
-                    idx - 1
-                } else {
-                    idx - 2
+            Self::BSP => match layout_options.bsp_strategy {
+                BspStrategy::Dwindle => {
+                    if idx.is_multiple_of(2) {
+                        idx - 1
+                    } else {
+                        idx - 2
+                    }
                 }
-            }
-            Self::Columns => unreachable!(),
+                bsp_strategy => {
+                    bsp_neighbour(OperationDirection::Up, idx, count, bsp_strategy).unwrap_or(idx)
+                }
+            },
+            Self::Columns | Self::Scrolling => unreachable!(),
             Self::Rows | Self::VerticalStack | Self::UltrawideVerticalStack => idx - 1,
             Self::HorizontalStack => 0,
+            Self::Grid => {
+                let (columns, rows) = grid_dimensions(count);
+                let last_row_cells = count - columns * (rows - 1);
+
+                if idx / columns == rows - 1 && last_row_cells != columns {
+                    // Moving out of the stretched last row, pick the cell above its midpoint
+                    let cell = idx % columns;
+                    (rows - 2) * columns + ((2 * cell + 1) * columns) / (2 * last_row_cells)
+                } else {
+                    idx - columns
+                }
+            }
+            Self::CenteredMaster => {
+                centered_master_neighbour(OperationDirection::Up, idx, count, layout_options)
+                    .unwrap_or(idx)
+            }
         }
     }
 
-    fn down_index(&self, idx: usize) -> usize {
+    fn down_index(&self, idx: usize, count: usize, layout_options: LayoutOptions) -> usize {
         match self {
-            Self::BSP | Self::Rows | Self::VerticalStack | Self::UltrawideVerticalStack => idx + 1,
-            Self::Columns => unreachable!(),
-            Self::HorizontalStack => 1,
+            Self::BSP => match layout_options.bsp_strategy {
+                BspStrategy::Dwindle => idx + 1,
+                bsp_strategy => {
+                    bsp_neighbour(OperationDirection::Down, idx, count, bsp_strategy).unwrap_or(idx)
+                }
+            },
+            Self::Rows | Self::VerticalStack | Self::UltrawideVerticalStack => idx + 1,
+            Self::Columns | Self::Scrolling => unreachable!(),
+            Self::HorizontalStack => layout_options.masters(count),
+            Self::Grid => {
+                let (columns, rows) = grid_dimensions(count);
+                let last_row_cells = count - columns * (rows - 1);
+
+                if idx / columns == rows - 2 && last_row_cells != columns {
+                    // Moving into the stretched last row, pick the cell below our midpoint
+                    let cell = idx % columns;
+                    (rows - 1) * columns + ((2 * cell + 1) * last_row_cells) / (2 * columns)
+                } else {
+                    idx + columns
+                }
+            }
+            Self::CenteredMaster => {
+                centered_master_neighbour(OperationDirection::Down, idx, count, layout_options)
+                    .unwrap_or(idx)
+            }
         }
     }
 
-    fn left_index(&self, idx: usize) -> usize {
+    fn left_index(&self, idx: usize, count: usize, layout_options: LayoutOptions) -> usize {
         match self {
-            Self::BSP => {
-                if idx % 2 == 0 {
-                    idx - 2
-                } else {
-                    idx - 1
+            Self::BSP => match layout_options.bsp_strategy {
+                BspStrategy::Dwindle => {
+                    if idx.is_multiple_of(2) {
+                        idx - 2
+                    } else {
+                        idx - 1
+                    }
                 }
-            }
-            Self::Columns | Self::HorizontalStack => idx - 1,
+                bsp_strategy => {
+                    bsp_neighbour(OperationDirection::Left, idx, count, bsp_strategy).unwrap_or(idx)
+                }
+            },
+            Self::Columns | Self::Scrolling | Self::HorizontalStack | Self::Grid => idx - 1,
             Self::Rows => unreachable!(),
             Self::VerticalStack => 0,
-            Self::UltrawideVerticalStack => match idx {
-                0 => 1,
-                1 => unreachable!(),
-                _ => 0,
-            },
+            Self::UltrawideVerticalStack => {
+                let masters = layout_options.masters(count);
+                if idx < masters {
+                    masters
+                } else {
+                    0
+                }
+            }
+            Self::CenteredMaster => {
+                centered_master_neighbour(OperationDirection::Left, idx, count, layout_options)
+                    .unwrap_or(idx)
+            }
         }
     }
 
-    fn right_index(&self, idx: usize) -> usize {
+    fn right_index(&self, idx: usize, count: usize, layout_options: LayoutOptions) -> usize {
         match self {
-            Self::BSP | Self::Columns | Self::HorizontalStack => idx + 1,
-            Self::Rows => unreachable!(),
-            Self::VerticalStack => 1,
-            Self::UltrawideVerticalStack => match idx {
-                1 => 0,
-                0 => 2,
-                _ => unreachable!(),
+            Self::BSP => match layout_options.bsp_strategy {
+                BspStrategy::Dwindle => idx + 1,
+                bsp_strategy => bsp_neighbour(OperationDirection::Right, idx, count, bsp_strategy)
+                    .unwrap_or(idx),
             },
+            Self::Columns | Self::Scrolling | Self::HorizontalStack | Self::Grid => idx + 1,
+            Self::Rows => unreachable!(),
+            Self::VerticalStack => layout_options.masters(count),
+            Self::UltrawideVerticalStack => {
+                let masters = layout_options.masters(count);
+                if idx == masters {
+                    0
+                } else {
+                    masters + 1
+                }
+            }
+            Self::CenteredMaster => {
+                centered_master_neighbour(OperationDirection::Right, idx, count, layout_options)
+                    .unwrap_or(idx)
+            }
         }
     }
 }
@@ -171,36 +321,51 @@ impl Direction for CustomLayout {
         op_direction: OperationDirection,
         idx: usize,
         count: usize,
+        layout_options: LayoutOptions,
     ) -> Option<usize> {
+        if matches!(self.orientation(), Orientation::Rows) {
+            return self.transposed().index_in_direction(
+                transpose(op_direction),
+                idx,
+                count,
+                layout_options,
+            );
+        }
+
         if count <= self.len() {
-            return DefaultLayout::Columns.index_in_direction(op_direction, idx, count);
+            return DefaultLayout::Columns.index_in_direction(
+                op_direction,
+                idx,
+                count,
+                layout_options,
+            );
         }
 
         match op_direction {
             OperationDirection::Left => {
-                if self.is_valid_direction(op_direction, idx, count) {
-                    Option::from(self.left_index(idx))
+                if self.is_valid_direction(op_direction, idx, count, layout_options) {
+                    Option::from(self.left_index(idx, count, layout_options))
                 } else {
                     None
                 }
             }
             OperationDirection::Right => {
-                if self.is_valid_direction(op_direction, idx, count) {
-                    Option::from(self.right_index(idx))
+                if self.is_valid_direction(op_direction, idx, count, layout_options) {
+                    Option::from(self.right_index(idx, count, layout_options))
                 } else {
                     None
                 }
             }
             OperationDirection::Up => {
-                if self.is_valid_direction(op_direction, idx, count) {
-                    Option::from(self.up_index(idx))
+                if self.is_valid_direction(op_direction, idx, count, layout_options) {
+                    Option::from(self.up_index(idx, count, layout_options))
                 } else {
                     None
                 }
             }
             OperationDirection::Down => {
-                if self.is_valid_direction(op_direction, idx, count) {
-                    Option::from(self.down_index(idx))
+                if self.is_valid_direction(op_direction, idx, count, layout_options) {
+                    Option::from(self.down_index(idx, count, layout_options))
                 } else {
                     None
                 }
@@ -213,15 +378,46 @@ impl Direction for CustomLayout {
         op_direction: OperationDirection,
         idx: usize,
         count: usize,
+        layout_options: LayoutOptions,
     ) -> bool {
+        if matches!(self.orientation(), Orientation::Rows) {
+            return self.transposed().is_valid_direction(
+                transpose(op_direction),
+                idx,
+                count,
+                layout_options,
+            );
+        }
+
         if count <= self.len() {
-            return DefaultLayout::Columns.is_valid_direction(op_direction, idx, count);
+            return DefaultLayout::Columns.is_valid_direction(
+                op_direction,
+                idx,
+                count,
+                layout_options,
+            );
         }
 
         match op_direction {
-            OperationDirection::Left => idx != 0 && self.column_for_container_idx(idx) != 0,
+            OperationDirection::Left => {
+                if idx == 0 {
+                    return false;
+                }
+
+                let (column_idx, column) = self.column_with_idx(idx);
+                column_idx != 0
+                    || (is_vertical_split(column)
+                        && self.column_for_container_idx(idx - 1) == column_idx)
+            }
             OperationDirection::Right => {
-                idx != count - 1 && self.column_for_container_idx(idx) != self.len() - 1
+                if idx == count - 1 {
+                    return false;
+                }
+
+                let (column_idx, column) = self.column_with_idx(idx);
+                column_idx != self.len() - 1
+                    || (is_vertical_split(column)
+                        && self.column_for_container_idx(idx + 1) == column_idx)
             }
             OperationDirection::Up => {
                 if idx == 0 {
@@ -229,7 +425,7 @@ impl Direction for CustomLayout {
                 }
 
                 let (column_idx, column) = self.column_with_idx(idx);
-                column.map_or(false, |column| match column {
+                column.is_some_and(|column| match column {
                     Column::Secondary(Some(ColumnSplitWithCapacity::Horizontal(_)))
                     | Column::Tertiary(ColumnSplit::Horizontal) => {
                         self.column_for_container_idx(idx - 1) == column_idx
@@ -243,7 +439,7 @@ impl Direction for CustomLayout {
                 }
 
                 let (column_idx, column) = self.column_with_idx(idx);
-                column.map_or(false, |column| match column {
+                column.is_some_and(|column| match column {
                     Column::Secondary(Some(ColumnSplitWithCapacity::Horizontal(_)))
                     | Column::Tertiary(ColumnSplit::Horizontal) => {
                         self.column_for_container_idx(idx + 1) == column_idx
@@ -254,25 +450,246 @@ impl Direction for CustomLayout {
         }
     }
 
-    fn up_index(&self, idx: usize) -> usize {
+    fn up_index(&self, idx: usize, count: usize, layout_options: LayoutOptions) -> usize {
+        if matches!(self.orientation(), Orientation::Rows) {
+            return self.transposed().left_index(idx, count, layout_options);
+        }
+
         idx - 1
     }
 
-    fn down_index(&self, idx: usize) -> usize {
+    fn down_index(&self, idx: usize, count: usize, layout_options: LayoutOptions) -> usize {
+        if matches!(self.orientation(), Orientation::Rows) {
+            return self.transposed().right_index(idx, count, layout_options);
+        }
+
         idx + 1
     }
 
-    fn left_index(&self, idx: usize) -> usize {
-        let column_idx = self.column_for_container_idx(idx);
-        if column_idx - 1 == 0 {
+    fn left_index(&self, idx: usize, count: usize, layout_options: LayoutOptions) -> usize {
+        if matches!(self.orientation(), Orientation::Rows) {
+            return self.transposed().up_index(idx, count, layout_options);
+        }
+
+        let (column_idx, column) = self.column_with_idx(idx);
+        if is_vertical_split(column) && self.column_for_container_idx(idx - 1) == column_idx {
+            return idx - 1;
+        }
+
+        if is_vertical_split(
+            self.get(column_idx - 1)
+                .map(|definition| &definition.column),
+        ) {
+            // Move into the right-most container of a column split into columns
+            self.first_container_idx(column_idx) - 1
+        } else if column_idx - 1 == 0 {
             0
         } else {
             self.first_container_idx(column_idx - 1)
         }
     }
 
-    fn right_index(&self, idx: usize) -> usize {
-        let column_idx = self.column_for_container_idx(idx);
+    fn right_index(&self, idx: usize, count: usize, layout_options: LayoutOptions) -> usize {
+        if matches!(self.orientation(), Orientation::Rows) {
+            return self.transposed().down_index(idx, count, layout_options);
+        }
+
+        let (column_idx, column) = self.column_with_idx(idx);
+        if is_vertical_split(column) && self.column_for_container_idx(idx + 1) == column_idx {
+            return idx + 1;
+        }
+
         self.first_container_idx(column_idx + 1)
     }
 }
+
+/// Columns split vertically have their containers laid out side by side
+const fn is_vertical_split(column: Option<&Column>) -> bool {
+    matches!(
+        column,
+        Some(
+            Column::Secondary(Some(ColumnSplitWithCapacity::Vertical(_)))
+                | Column::Tertiary(ColumnSplit::Vertical)
+        )
+    )
+}
+
+/// Swaps horizontal and vertical directions for layouts calculated in a transposed area
+const fn transpose(op_direction: OperationDirection) -> OperationDirection {
+    match op_direction {
+        OperationDirection::Left => OperationDirection::Up,
+        OperationDirection::Right => OperationDirection::Down,
+        OperationDirection::Up => OperationDirection::Left,
+        OperationDirection::Down => OperationDirection::Right,
+    }
+}
+
+/// Finds neighbours from the `Vec<Rect>` calculated by an `Arrangement` instead of from indices,
+/// which means that resize adjustments and flips are already taken into account
+pub struct Geometric<'a> {
+    /// The calculated position of each container
+    pub layouts: &'a [Rect],
+    /// Container indices ordered from the most to the least recently focused
+    pub focus_history: &'a [usize],
+}
+
+impl Geometric<'_> {
+    #[must_use]
+    pub fn neighbour(&self, op_direction: OperationDirection, idx: usize) -> Option<usize> {
+        let current = self.layouts.get(idx)?;
+
+        // Distance between the facing edges and overlap on the perpendicular axis
+        let measure = |candidate: &Rect| -> Option<(i32, i32)> {
+            let (distance, overlap) = match op_direction {
+                OperationDirection::Left => (
+                    current.left - (candidate.left + candidate.right),
+                    vertical_overlap(current, candidate),
+                ),
+                OperationDirection::Right => (
+                    candidate.left - (current.left + current.right),
+                    vertical_overlap(current, candidate),
+                ),
+                OperationDirection::Up => (
+                    current.top - (candidate.top + candidate.bottom),
+                    horizontal_overlap(current, candidate),
+                ),
+                OperationDirection::Down => (
+                    candidate.top - (current.top + current.bottom),
+                    horizontal_overlap(current, candidate),
+                ),
+            };
+
+            if distance >= 0 && overlap > 0 {
+                Option::from((distance, overlap))
+            } else {
+                None
+            }
+        };
+
+        let recency = |candidate_idx: usize| {
+            self.focus_history
+                .iter()
+                .position(|i| *i == candidate_idx)
+                .unwrap_or(usize::MAX)
+        };
+
+        self.layouts
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != idx)
+            .filter_map(|(i, candidate)| measure(candidate).map(|m| (i, m)))
+            .min_by(
+                |(a_idx, (a_distance, a_overlap)), (b_idx, (b_distance, b_overlap))| {
+                    a_distance
+                        .cmp(b_distance)
+                        .then_with(|| recency(*a_idx).cmp(&recency(*b_idx)))
+                        .then_with(|| b_overlap.cmp(a_overlap))
+                        .then_with(|| a_idx.cmp(b_idx))
+                },
+            )
+            .map(|(i, _)| i)
+    }
+}
+
+const fn vertical_overlap(a: &Rect, b: &Rect) -> i32 {
+    let top = if a.top > b.top { a.top } else { b.top };
+    let a_bottom = a.top + a.bottom;
+    let b_bottom = b.top + b.bottom;
+    let bottom = if a_bottom < b_bottom {
+        a_bottom
+    } else {
+        b_bottom
+    };
+
+    bottom - top
+}
+
+const fn horizontal_overlap(a: &Rect, b: &Rect) -> i32 {
+    let left = if a.left > b.left { a.left } else { b.left };
+    let a_right = a.left + a.right;
+    let b_right = b.left + b.right;
+    let right = if a_right < b_right { a_right } else { b_right };
+
+    right - left
+}
+
+impl Direction for TreeLayout {
+    fn index_in_direction(
+        &self,
+        op_direction: OperationDirection,
+        idx: usize,
+        count: usize,
+        _layout_options: LayoutOptions,
+    ) -> Option<usize> {
+        self.neighbour(op_direction, idx, count)
+    }
+
+    fn is_valid_direction(
+        &self,
+        op_direction: OperationDirection,
+        idx: usize,
+        count: usize,
+        _layout_options: LayoutOptions,
+    ) -> bool {
+        self.neighbour(op_direction, idx, count).is_some()
+    }
+
+    fn up_index(&self, idx: usize, count: usize, _layout_options: LayoutOptions) -> usize {
+        self.neighbour(OperationDirection::Up, idx, count)
+            .unwrap_or(idx)
+    }
+
+    fn down_index(&self, idx: usize, count: usize, _layout_options: LayoutOptions) -> usize {
+        self.neighbour(OperationDirection::Down, idx, count)
+            .unwrap_or(idx)
+    }
+
+    fn left_index(&self, idx: usize, count: usize, _layout_options: LayoutOptions) -> usize {
+        self.neighbour(OperationDirection::Left, idx, count)
+            .unwrap_or(idx)
+    }
+
+    fn right_index(&self, idx: usize, count: usize, _layout_options: LayoutOptions) -> usize {
+        self.neighbour(OperationDirection::Right, idx, count)
+            .unwrap_or(idx)
+    }
+}
+
+impl Direction for Geometric<'_> {
+    fn index_in_direction(
+        &self,
+        op_direction: OperationDirection,
+        idx: usize,
+        _count: usize,
+        _layout_options: LayoutOptions,
+    ) -> Option<usize> {
+        self.neighbour(op_direction, idx)
+    }
+
+    fn is_valid_direction(
+        &self,
+        op_direction: OperationDirection,
+        idx: usize,
+        _count: usize,
+        _layout_options: LayoutOptions,
+    ) -> bool {
+        self.neighbour(op_direction, idx).is_some()
+    }
+
+    fn up_index(&self, idx: usize, _count: usize, _layout_options: LayoutOptions) -> usize {
+        self.neighbour(OperationDirection::Up, idx).unwrap_or(idx)
+    }
+
+    fn down_index(&self, idx: usize, _count: usize, _layout_options: LayoutOptions) -> usize {
+        self.neighbour(OperationDirection::Down, idx).unwrap_or(idx)
+    }
+
+    fn left_index(&self, idx: usize, _count: usize, _layout_options: LayoutOptions) -> usize {
+        self.neighbour(OperationDirection::Left, idx).unwrap_or(idx)
+    }
+
+    fn right_index(&self, idx: usize, _count: usize, _layout_options: LayoutOptions) -> usize {
+        self.neighbour(OperationDirection::Right, idx)
+            .unwrap_or(idx)
+    }
+}
@@ -1,24 +1,76 @@
+use std::path::Path;
+
+use color_eyre::Result;
 use schemars::JsonSchema;
 use serde::Deserialize;
 use serde::Serialize;
 
+use crate::custom_layout::deserialize_file;
+use crate::layout_validation::ensure_valid;
+use crate::layout_validation::LayoutError;
 use crate::Arrangement;
 use crate::CustomLayout;
 use crate::DefaultLayout;
 use crate::Direction;
+use crate::OperationDirection;
+use crate::Rect;
+use crate::Sizing;
+use crate::TreeLayout;
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub enum Layout {
     Default(DefaultLayout),
     Custom(CustomLayout),
+    Tree(TreeLayout),
+}
+
+/// The formats that a custom layout file can be written in
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum CustomLayoutFile {
+    Columns(CustomLayout),
+    Tree(TreeLayout),
 }
 
 impl Layout {
+    /// Loads a custom layout file written either as a list of columns or as a tree of splits
+    pub fn from_custom_path<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let layout = match deserialize_file(path.as_ref())? {
+            CustomLayoutFile::Columns(layout) => {
+                ensure_valid(&layout.validate())?;
+                Self::Custom(layout)
+            }
+            CustomLayoutFile::Tree(layout) => {
+                ensure_valid(&layout.validate())?;
+                Self::Tree(layout)
+            }
+        };
+
+        Ok(layout)
+    }
+
+    /// Checks a custom layout file, returning every problem with the layout that it describes, or
+    /// an error explaining why the file couldn't be parsed
+    pub fn validate_custom_path<P: AsRef<Path>>(path: P) -> Result<Vec<LayoutError>> {
+        let path = path.as_ref();
+
+        // The file is parsed as the kind of layout that its shape suggests, rather than trying
+        // each kind in turn, so that a parsing error points at the problem in the file
+        let value: serde_json::Value = deserialize_file(path)?;
+
+        Ok(if value.is_array() || value.get("columns").is_some() {
+            CustomLayout::from_path_with_shape(path, value.is_array())?.validate()
+        } else {
+            deserialize_file::<TreeLayout>(path)?.validate()
+        })
+    }
+
     #[must_use]
     pub fn as_boxed_direction(&self) -> Box<dyn Direction> {
         match self {
             Layout::Default(layout) => Box::new(*layout),
             Layout::Custom(layout) => Box::new(layout.clone()),
+            Layout::Tree(layout) => Box::new(layout.clone()),
         }
     }
 
@@ -27,6 +79,107 @@ impl Layout {
         match self {
             Layout::Default(layout) => Box::new(*layout),
             Layout::Custom(layout) => Box::new(layout.clone()),
+            Layout::Tree(layout) => Box::new(layout.clone()),
+        }
+    }
+
+    /// Calculates the resize adjustment of a container after moving one of its edges
+    #[must_use]
+    #[allow(clippy::cast_precision_loss, clippy::unused_self)]
+    pub fn resize(
+        &self,
+        unaltered: &Rect,
+        resize: &Option<Rect>,
+        edge: OperationDirection,
+        sizing: Sizing,
+        delta: i32,
+    ) -> Option<Rect> {
+        let max_divisor = 1.005;
+        let mut r = resize.unwrap_or_default();
+
+        let resize_delta = delta;
+
+        match edge {
+            OperationDirection::Left => match sizing {
+                Sizing::Increase => {
+                    // Some final checks to make sure the user can't infinitely resize to
+                    // the point of pushing other windows out of bounds
+
+                    // Note: These checks cannot take into account the changes made to the
+                    // edges of adjacent windows at operation time, so it is still possible
+                    // to push windows out of bounds by maxing out an Increase Left on a
+                    // Window with index 1, and then maxing out a Decrease Right on a Window
+                    // with index 0. I don't think it's worth trying to defensively program
+                    // against this; if people end up in this situation they are better off
+                    // just hitting the retile command
+                    let diff = ((r.left + -resize_delta) as f32).abs();
+                    let max = unaltered.right as f32 / max_divisor;
+                    if diff < max {
+                        r.left += -resize_delta;
+                    }
+                }
+                Sizing::Decrease => {
+                    let diff = ((r.left - -resize_delta) as f32).abs();
+                    let max = unaltered.right as f32 / max_divisor;
+                    if diff < max {
+                        r.left -= -resize_delta;
+                    }
+                }
+            },
+            OperationDirection::Up => match sizing {
+                Sizing::Increase => {
+                    let diff = ((r.top + resize_delta) as f32).abs();
+                    let max = unaltered.bottom as f32 / max_divisor;
+                    if diff < max {
+                        r.top += -resize_delta;
+                    }
+                }
+                Sizing::Decrease => {
+                    let diff = ((r.top - resize_delta) as f32).abs();
+                    let max = unaltered.bottom as f32 / max_divisor;
+                    if diff < max {
+                        r.top -= -resize_delta;
+                    }
+                }
+            },
+            OperationDirection::Right => match sizing {
+                Sizing::Increase => {
+                    let diff = ((r.right + resize_delta) as f32).abs();
+                    let max = unaltered.right as f32 / max_divisor;
+                    if diff < max {
+                        r.right += resize_delta;
+                    }
+                }
+                Sizing::Decrease => {
+                    let diff = ((r.right - resize_delta) as f32).abs();
+                    let max = unaltered.right as f32 / max_divisor;
+                    if diff < max {
+                        r.right -= resize_delta;
+                    }
+                }
+            },
+            OperationDirection::Down => match sizing {
+                Sizing::Increase => {
+                    let diff = ((r.bottom + resize_delta) as f32).abs();
+                    let max = unaltered.bottom as f32 / max_divisor;
+                    if diff < max {
+                        r.bottom += resize_delta;
+                    }
+                }
+                Sizing::Decrease => {
+                    let diff = ((r.bottom - resize_delta) as f32).abs();
+                    let max = unaltered.bottom as f32 / max_divisor;
+                    if diff < max {
+                        r.bottom -= resize_delta;
+                    }
+                }
+            },
+        }
+
+        if r.eq(&Rect::default()) {
+            None
+        } else {
+            Option::from(r)
         }
     }
 }
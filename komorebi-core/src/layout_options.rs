@@ -0,0 +1,175 @@
+use clap::ValueEnum;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use strum::Display;
+use strum::EnumString;
+
+use crate::Sizing;
+
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    Display,
+    EnumString,
+    ValueEnum,
+    JsonSchema,
+)]
+#[strum(serialize_all = "snake_case")]
+pub enum BspStrategy {
+    /// Each container is split off toward the bottom-right of the previous one
+    #[default]
+    Dwindle,
+    /// Each container is split off in a direction rotating clockwise from the previous one
+    Spiral,
+    /// The largest remaining container is split in half for each new container
+    Balanced,
+}
+
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    Display,
+    EnumString,
+    ValueEnum,
+    JsonSchema,
+)]
+#[strum(serialize_all = "snake_case")]
+pub enum StackPlacement {
+    /// Stack containers alternate between the left and the right of the center column
+    #[default]
+    Alternate,
+    /// The first half of the stack containers are placed on the left of the center column
+    FillLeft,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct LayoutOptions {
+    /// Ratio of the work area taken up by the main area of stack layouts
+    pub main_ratio: f32,
+    /// Number of containers in the main area of stack layouts
+    pub master_count: usize,
+    /// Strategy used to split containers in the BSP layout
+    pub bsp_strategy: BspStrategy,
+    /// Ratio of the work area taken up by each column of the scrolling layout
+    pub column_ratio: f32,
+    /// How stack containers are placed on either side of the centered master layout
+    pub stack_placement: StackPlacement,
+    /// Maximum width of a lone container in the centered master layout
+    pub single_container_width: Option<i32>,
+}
+
+impl Default for LayoutOptions {
+    fn default() -> Self {
+        Self {
+            main_ratio: Self::DEFAULT_MAIN_RATIO,
+            master_count: 1,
+            bsp_strategy: BspStrategy::default(),
+            column_ratio: Self::DEFAULT_COLUMN_RATIO,
+            stack_placement: StackPlacement::default(),
+            single_container_width: None,
+        }
+    }
+}
+
+impl LayoutOptions {
+    pub const DEFAULT_MAIN_RATIO: f32 = 0.5;
+    pub const MIN_MAIN_RATIO: f32 = 0.1;
+    pub const MAX_MAIN_RATIO: f32 = 0.9;
+    pub const DEFAULT_COLUMN_RATIO: f32 = 0.5;
+    pub const MIN_COLUMN_RATIO: f32 = 0.1;
+    pub const MAX_COLUMN_RATIO: f32 = 1.0;
+
+    #[must_use]
+    pub const fn with_main_ratio(mut self, main_ratio: f32) -> Self {
+        self.main_ratio = main_ratio.clamp(Self::MIN_MAIN_RATIO, Self::MAX_MAIN_RATIO);
+        self
+    }
+
+    #[must_use]
+    pub fn with_master_count(mut self, master_count: usize) -> Self {
+        self.master_count = master_count.max(1);
+        self
+    }
+
+    #[must_use]
+    pub const fn with_column_ratio(mut self, column_ratio: f32) -> Self {
+        self.column_ratio = column_ratio.clamp(Self::MIN_COLUMN_RATIO, Self::MAX_COLUMN_RATIO);
+        self
+    }
+
+    pub fn adjust_main_ratio(&mut self, sizing: Sizing, adjustment: f32) {
+        let main_ratio = match sizing {
+            Sizing::Increase => self.main_ratio + adjustment,
+            Sizing::Decrease => self.main_ratio - adjustment,
+        };
+
+        *self = self.with_main_ratio(main_ratio);
+    }
+
+    pub fn adjust_master_count(&mut self, sizing: Sizing, adjustment: usize) {
+        let master_count = match sizing {
+            Sizing::Increase => self.master_count.saturating_add(adjustment),
+            Sizing::Decrease => self.master_count.saturating_sub(adjustment),
+        };
+
+        *self = self.with_master_count(master_count);
+    }
+
+    pub fn adjust_column_ratio(&mut self, sizing: Sizing, adjustment: f32) {
+        let column_ratio = match sizing {
+            Sizing::Increase => self.column_ratio + adjustment,
+            Sizing::Decrease => self.column_ratio - adjustment,
+        };
+
+        *self = self.with_column_ratio(column_ratio);
+    }
+
+    /// The number of masters actually on screen, never less than one
+    #[must_use]
+    pub fn masters(&self, count: usize) -> usize {
+        self.master_count.max(1).min(count)
+    }
+
+    /// The size of the main area out of a given total dimension
+    #[must_use]
+    #[allow(
+        clippy::cast_precision_loss,
+        clippy::cast_possible_truncation,
+        clippy::cast_possible_wrap
+    )]
+    pub fn main_size(&self, total: i32) -> i32 {
+        let main_ratio = self
+            .main_ratio
+            .clamp(Self::MIN_MAIN_RATIO, Self::MAX_MAIN_RATIO);
+
+        (total as f32 * main_ratio) as i32
+    }
+
+    /// The width of each column of the scrolling layout out of a given total width
+    #[must_use]
+    #[allow(
+        clippy::cast_precision_loss,
+        clippy::cast_possible_truncation,
+        clippy::cast_possible_wrap
+    )]
+    pub fn column_width(&self, total: i32) -> i32 {
+        let column_ratio = self
+            .column_ratio
+            .clamp(Self::MIN_COLUMN_RATIO, Self::MAX_COLUMN_RATIO);
+
+        (total as f32 * column_ratio) as i32
+    }
+}
@@ -0,0 +1,120 @@
+use clap::ValueEnum;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use strum::Display;
+use strum::EnumString;
+
+use crate::Rect;
+
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    Display,
+    EnumString,
+    ValueEnum,
+    JsonSchema,
+)]
+#[strum(serialize_all = "snake_case")]
+pub enum WorkAreaOrientation {
+    /// The work area is at least as wide as it is tall
+    Landscape,
+    /// The work area is taller than it is wide
+    Portrait,
+}
+
+impl From<&Rect> for WorkAreaOrientation {
+    fn from(work_area: &Rect) -> Self {
+        if work_area.right >= work_area.bottom {
+            Self::Landscape
+        } else {
+            Self::Portrait
+        }
+    }
+}
+
+/// The conditions under which a layout rule applies, all of which must be met
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct LayoutCondition {
+    /// Minimum number of containers on the workspace (default: 0)
+    #[serde(default)]
+    pub containers: usize,
+    /// Orientation of the work area (default: any)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub orientation: Option<WorkAreaOrientation>,
+    /// Minimum ratio of the width of the work area to its height (default: any)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_aspect_ratio: Option<f32>,
+    /// Maximum ratio of the width of the work area to its height (default: any)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_aspect_ratio: Option<f32>,
+    /// Minimum width of the work area in pixels (default: any)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_width: Option<i32>,
+    /// Maximum width of the work area in pixels (default: any)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_width: Option<i32>,
+    /// Minimum height of the work area in pixels (default: any)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_height: Option<i32>,
+    /// Maximum height of the work area in pixels (default: any)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_height: Option<i32>,
+    /// Name, device or device id of the monitor (default: any)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub monitor: Option<String>,
+}
+
+impl From<usize> for LayoutCondition {
+    fn from(containers: usize) -> Self {
+        Self {
+            containers,
+            ..Default::default()
+        }
+    }
+}
+
+impl LayoutCondition {
+    /// The container count of a condition which doesn't depend on the monitor
+    #[must_use]
+    pub fn container_count_only(&self) -> Option<usize> {
+        if *self == Self::from(self.containers) {
+            Option::from(self.containers)
+        } else {
+            None
+        }
+    }
+
+    /// Whether this condition is met by a workspace with the given number of containers, laid out
+    /// in the given work area on a monitor known by any of the given identifiers
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn matches(&self, containers: usize, work_area: &Rect, monitor: &[String]) -> bool {
+        let aspect_ratio = if work_area.bottom > 0 {
+            work_area.right as f32 / work_area.bottom as f32
+        } else {
+            0.0
+        };
+
+        containers >= self.containers
+            && self
+                .orientation
+                .is_none_or(|orientation| orientation == WorkAreaOrientation::from(work_area))
+            && self.min_aspect_ratio.is_none_or(|min| aspect_ratio >= min)
+            && self.max_aspect_ratio.is_none_or(|max| aspect_ratio <= max)
+            && self.min_width.is_none_or(|min| work_area.right >= min)
+            && self.max_width.is_none_or(|max| work_area.right <= max)
+            && self.min_height.is_none_or(|min| work_area.bottom >= min)
+            && self.max_height.is_none_or(|max| work_area.bottom <= max)
+            && self.monitor.as_ref().is_none_or(|name| {
+                monitor
+                    .iter()
+                    .any(|identifier| identifier.eq_ignore_ascii_case(name))
+            })
+    }
+}
@@ -0,0 +1,128 @@
+use std::fmt::Display;
+use std::fmt::Formatter;
+
+use color_eyre::eyre::bail;
+use color_eyre::Result;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// A problem which makes a custom layout invalid, along with where it was found in the layout
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct LayoutError {
+    /// The part of the layout with the problem, such as `columns[2]` or `root.children[1]`
+    pub location: String,
+    pub kind: LayoutErrorKind,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type", content = "content")]
+pub enum LayoutErrorKind {
+    /// The layout doesn't have any columns
+    NoColumns,
+    /// None of the columns is a primary column
+    MissingPrimary,
+    /// There is more than one primary column
+    MultiplePrimaries,
+    /// None of the columns is a tertiary column
+    MissingTertiary,
+    /// There is more than one tertiary column
+    MultipleTertiaries,
+    /// The final column is not the tertiary column
+    FinalColumnNotTertiary,
+    /// A secondary column is split without room for any containers
+    UnsupportedSplit,
+    /// A column width percentage which doesn't leave space for the other columns
+    BadPercentage(f32),
+    /// A column width in pixels which is not positive
+    BadPixels(i32),
+    /// The column width percentages add up to more than the area, or leave no space for the
+    /// columns without a configured width
+    PercentagesExceedArea(f32),
+    /// The tree doesn't have a leaf for containers which don't fit in the other leaves
+    MissingOverflow,
+    /// There is more than one overflow leaf
+    MultipleOverflows,
+    /// A node with children is marked as the overflow leaf
+    OverflowBranch,
+    /// A node with children has a capacity
+    BranchCapacity,
+    /// The overflow leaf has a capacity
+    OverflowCapacity,
+    /// A leaf without room for any containers
+    EmptyLeaf,
+    /// A node ratio which doesn't leave space for the other children of its parent
+    BadRatio(f32),
+    /// The ratios of the children of a node add up to more than the node, or leave no space for
+    /// the children without a configured ratio
+    RatiosExceedArea(f32),
+}
+
+impl Display for LayoutErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NoColumns => write!(f, "the layout must have at least one column"),
+            Self::MissingPrimary => write!(f, "the layout must have a primary column"),
+            Self::MultiplePrimaries => write!(f, "the layout can only have one primary column"),
+            Self::MissingTertiary => write!(f, "the layout must have a tertiary column"),
+            Self::MultipleTertiaries => write!(f, "the layout can only have one tertiary column"),
+            Self::FinalColumnNotTertiary => write!(f, "the final column must be the tertiary column"),
+            Self::UnsupportedSplit => write!(f, "a split secondary column must hold at least one container"),
+            Self::BadPercentage(percentage) => write!(
+                f,
+                "a width of {percentage}% must be greater than 0% and less than 100%"
+            ),
+            Self::BadPixels(pixels) => {
+                write!(f, "a width of {pixels} pixels must be greater than 0")
+            }
+            Self::PercentagesExceedArea(total) => write!(
+                f,
+                "the column widths add up to {total}%, which leaves no space for the other columns"
+            ),
+            Self::MissingOverflow => write!(f, "the tree must have one leaf with \"overflow\" set"),
+            Self::MultipleOverflows => write!(f, "the tree can only have one leaf with \"overflow\" set"),
+            Self::OverflowBranch => write!(f, "only a leaf can have \"overflow\" set"),
+            Self::BranchCapacity => write!(f, "only a leaf can have a capacity"),
+            Self::OverflowCapacity => write!(f, "the overflow leaf can't have a capacity"),
+            Self::EmptyLeaf => write!(f, "a leaf must have a capacity of at least 1"),
+            Self::BadRatio(ratio) => {
+                write!(f, "a ratio of {ratio} must be greater than 0 and less than 1")
+            }
+            Self::RatiosExceedArea(total) => write!(
+                f,
+                "the ratios of the children add up to {total}, which leaves no space for the other children"
+            ),
+        }
+    }
+}
+
+impl Display for LayoutError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.location, self.kind)
+    }
+}
+
+impl LayoutError {
+    pub(crate) fn new(location: impl Into<String>, kind: LayoutErrorKind) -> Self {
+        Self {
+            location: location.into(),
+            kind,
+        }
+    }
+}
+
+/// Fails with every problem that was found in a layout, if there were any
+pub(crate) fn ensure_valid(errors: &[LayoutError]) -> Result<()> {
+    if !errors.is_empty() {
+        bail!(
+            "the layout file provided was invalid: {}",
+            errors
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join("; ")
+        );
+    }
+
+    Ok(())
+}
@@ -16,23 +16,52 @@ use strum::EnumString;
 
 pub use arrangement::Arrangement;
 pub use arrangement::Axis;
+pub use arrangement::LayoutFinding;
+pub use constraints::ContainerConstraints;
 pub use custom_layout::CustomLayout;
 pub use cycle_direction::CycleDirection;
 pub use default_layout::DefaultLayout;
 pub use direction::Direction;
+pub use direction::DirectionStrategy;
 pub use layout::Layout;
+pub use layout_options::BspStrategy;
+pub use layout_options::LayoutOptions;
+pub use layout_options::StackPlacement;
+pub use layout_rule::LayoutCondition;
+pub use layout_rule::WorkAreaOrientation;
+pub use layout_validation::LayoutError;
+pub use layout_validation::LayoutErrorKind;
 pub use operation_direction::OperationDirection;
+pub use padding::Padding;
+pub use preview::LayoutPreview;
+pub use preview::PreviewFormat;
 pub use rect::Rect;
+pub use resize_ratio::ResizeRatio;
+pub use scrolling::scroll_offset;
+pub use size_limit::SizeLimit;
+pub use tree_layout::TreeLayout;
 
 pub mod arrangement;
+mod bsp;
+mod centered_master;
 pub mod config_generation;
+pub mod constraints;
 pub mod custom_layout;
 pub mod cycle_direction;
 pub mod default_layout;
 pub mod direction;
 pub mod layout;
+pub mod layout_options;
+pub mod layout_rule;
+pub mod layout_validation;
 pub mod operation_direction;
+pub mod padding;
+pub mod preview;
 pub mod rect;
+pub mod resize_ratio;
+mod scrolling;
+pub mod size_limit;
+pub mod tree_layout;
 
 #[derive(Clone, Debug, Serialize, Deserialize, Display, JsonSchema)]
 #[serde(tag = "type", content = "content")]
@@ -45,6 +74,7 @@ pub enum SocketMessage {
     StackWindow(OperationDirection),
     ResizeWindowEdge(OperationDirection, Sizing),
     ResizeWindowAxis(Axis, Sizing),
+    Balance(BalanceScope),
     UnstackWindow,
     CycleStack(CycleDirection),
     MoveContainerToMonitorNumber(usize),
@@ -68,6 +98,13 @@ pub enum SocketMessage {
     ToggleFloat,
     ToggleMonocle,
     ToggleMaximize,
+    SendToScratchpad(Option<String>),
+    ToggleScratchpad(String),
+    CycleScratchpad(CycleDirection),
+    Mark(String),
+    FocusMark(String),
+    SwapWithMark(String),
+    Unmark(String),
     ToggleWindowContainerBehaviour,
     WindowHidingBehaviour(HidingBehaviour),
     ToggleCrossMonitorMoveBehaviour,
@@ -78,6 +115,9 @@ pub enum SocketMessage {
     UnmanageFocusedWindow,
     AdjustContainerPadding(Sizing, i32),
     AdjustWorkspacePadding(Sizing, i32),
+    AdjustMainRatio(Sizing, f32),
+    AdjustMasterCount(Sizing, usize),
+    AdjustColumnRatio(Sizing, f32),
     ChangeLayout(DefaultLayout),
     CycleLayout(CycleDirection),
     ChangeLayoutCustom(PathBuf),
@@ -96,6 +136,10 @@ pub enum SocketMessage {
     QuickLoad,
     Save(PathBuf),
     Load(PathBuf),
+    SaveSnapshot(String),
+    LoadSnapshot(String),
+    Undo,
+    Redo,
     CycleFocusMonitor(CycleDirection),
     CycleFocusWorkspace(CycleDirection),
     FocusMonitorNumber(usize),
@@ -104,14 +148,20 @@ pub enum SocketMessage {
     FocusWorkspaceNumbers(usize),
     FocusMonitorWorkspaceNumber(usize, usize),
     FocusNamedWorkspace(String),
-    ContainerPadding(usize, usize, i32),
-    NamedWorkspaceContainerPadding(String, i32),
-    FocusedWorkspaceContainerPadding(i32),
-    WorkspacePadding(usize, usize, i32),
-    NamedWorkspacePadding(String, i32),
-    FocusedWorkspacePadding(i32),
+    ContainerPadding(usize, usize, Padding),
+    NamedWorkspaceContainerPadding(String, Padding),
+    FocusedWorkspaceContainerPadding(Padding),
+    WorkspacePadding(usize, usize, Padding),
+    NamedWorkspacePadding(String, Padding),
+    FocusedWorkspacePadding(Padding),
     WorkspaceTiling(usize, usize, bool),
     NamedWorkspaceTiling(String, bool),
+    WorkspaceDirectionStrategy(usize, usize, DirectionStrategy),
+    NamedWorkspaceDirectionStrategy(String, DirectionStrategy),
+    WorkspaceBspStrategy(usize, usize, BspStrategy),
+    NamedWorkspaceBspStrategy(String, BspStrategy),
+    WorkspaceMaxSize(usize, usize, Option<SizeLimit>, Option<SizeLimit>),
+    NamedWorkspaceMaxSize(String, Option<SizeLimit>, Option<SizeLimit>),
     WorkspaceName(usize, usize, String),
     WorkspaceLayout(usize, usize, DefaultLayout),
     NamedWorkspaceLayout(String, DefaultLayout),
@@ -143,12 +193,14 @@ pub enum SocketMessage {
     NamedWorkspaceRule(ApplicationIdentifier, String, String),
     FloatRule(ApplicationIdentifier, String),
     ManageRule(ApplicationIdentifier, String),
+    ScratchpadRule(ApplicationIdentifier, String),
     IdentifyObjectNameChangeApplication(ApplicationIdentifier, String),
     IdentifyTrayApplication(ApplicationIdentifier, String),
     IdentifyLayeredApplication(ApplicationIdentifier, String),
     IdentifyBorderOverflowApplication(ApplicationIdentifier, String),
     State,
     VisibleWindows,
+    CheckLayout,
     Query(StateQuery),
     FocusFollowsMouse(FocusFollowsMouseImplementation, bool),
     ToggleFocusFollowsMouse(FocusFollowsMouseImplementation),
@@ -169,6 +221,58 @@ impl SocketMessage {
     pub fn as_bytes(&self) -> Result<Vec<u8>> {
         Ok(serde_json::to_string(self)?.as_bytes().to_vec())
     }
+
+    /// Whether the message changes which windows are in which containers, workspaces and
+    /// monitors, or how the focused workspace is laid out, so that it can be undone
+    #[must_use]
+    pub const fn changes_arrangement(&self) -> bool {
+        matches!(
+            self,
+            Self::MoveWindow(_)
+                | Self::CycleMoveWindow(_)
+                | Self::StackWindow(_)
+                | Self::ResizeWindowEdge(_, _)
+                | Self::ResizeWindowAxis(_, _)
+                | Self::Balance(_)
+                | Self::UnstackWindow
+                | Self::MoveContainerToMonitorNumber(_)
+                | Self::CycleMoveContainerToMonitor(_)
+                | Self::MoveContainerToWorkspaceNumber(_)
+                | Self::MoveContainerToNamedWorkspace(_)
+                | Self::CycleMoveContainerToWorkspace(_)
+                | Self::SendContainerToMonitorNumber(_)
+                | Self::CycleSendContainerToMonitor(_)
+                | Self::SendContainerToWorkspaceNumber(_)
+                | Self::CycleSendContainerToWorkspace(_)
+                | Self::SendContainerToMonitorWorkspaceNumber(_, _)
+                | Self::SendContainerToNamedWorkspace(_)
+                | Self::MoveWorkspaceToMonitorNumber(_)
+                | Self::SwapWorkspacesToMonitorNumber(_)
+                | Self::Promote
+                | Self::PromoteFocus
+                | Self::ToggleFloat
+                | Self::ToggleMonocle
+                | Self::ToggleMaximize
+                | Self::SwapWithMark(_)
+                | Self::SendToScratchpad(_)
+                | Self::ToggleScratchpad(_)
+                | Self::CycleScratchpad(_)
+                | Self::ManageFocusedWindow
+                | Self::UnmanageFocusedWindow
+                | Self::AdjustContainerPadding(_, _)
+                | Self::AdjustWorkspacePadding(_, _)
+                | Self::AdjustMainRatio(_, _)
+                | Self::AdjustMasterCount(_, _)
+                | Self::AdjustColumnRatio(_, _)
+                | Self::ChangeLayout(_)
+                | Self::CycleLayout(_)
+                | Self::ChangeLayoutCustom(_)
+                | Self::FlipLayout(_)
+                | Self::QuickLoad
+                | Self::Load(_)
+                | Self::LoadSnapshot(_)
+        )
+    }
 }
 
 impl FromStr for SocketMessage {
@@ -280,6 +384,23 @@ pub enum OperationBehaviour {
     NoOp,
 }
 
+#[derive(
+    Clone, Copy, Debug, Serialize, Deserialize, Display, EnumString, ValueEnum, JsonSchema,
+)]
+#[strum(serialize_all = "snake_case")]
+pub enum BalanceScope {
+    /// Reset every resize adjustment on the focused workspace
+    Workspace,
+    /// Reset the heights of the containers in the same column as the focused container
+    Column,
+    /// Reset the widths of the containers in the same row as the focused container
+    Row,
+    /// Reset the widths of every container on the focused workspace
+    Horizontal,
+    /// Reset the heights of every container on the focused workspace
+    Vertical,
+}
+
 #[derive(
     Clone, Copy, Debug, Serialize, Deserialize, Display, EnumString, ValueEnum, JsonSchema,
 )]
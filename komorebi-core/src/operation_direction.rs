@@ -9,6 +9,7 @@ use strum::EnumString;
 
 use crate::direction::Direction;
 use crate::Axis;
+use crate::LayoutOptions;
 
 #[derive(
     Clone, Copy, Debug, Serialize, Deserialize, Display, EnumString, ValueEnum, JsonSchema,
@@ -58,9 +59,10 @@ impl OperationDirection {
         self,
         layout: &dyn Direction,
         layout_flip: Option<Axis>,
+        layout_options: LayoutOptions,
         idx: usize,
         len: NonZeroUsize,
     ) -> Option<usize> {
-        layout.index_in_direction(self.flip(layout_flip), idx, len.get())
+        layout.index_in_direction(self.flip(layout_flip), idx, len.get(), layout_options)
     }
 }
@@ -0,0 +1,167 @@
+use std::str::FromStr;
+
+use color_eyre::eyre::anyhow;
+use color_eyre::eyre::bail;
+use color_eyre::Report;
+use schemars::gen::SchemaGenerator;
+use schemars::schema::Schema;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::Sizing;
+
+/// Padding applied to each side of an area
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(from = "PaddingValue", into = "PaddingValue")]
+pub struct Padding {
+    pub top: i32,
+    pub right: i32,
+    pub bottom: i32,
+    pub left: i32,
+}
+
+// Padding is written as a single integer when every side is the same, which keeps configuration
+// files from before per-side padding was supported valid
+
+/// Padding applied to each side of an area, either as one size for every side or a size per side
+#[derive(Clone, Copy, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+enum PaddingValue {
+    /// The same padding on every side
+    Uniform(i32),
+    /// Different padding on each side, where any side which isn't given has no padding
+    Sides {
+        #[serde(default)]
+        top: i32,
+        #[serde(default)]
+        right: i32,
+        #[serde(default)]
+        bottom: i32,
+        #[serde(default)]
+        left: i32,
+    },
+}
+
+impl From<PaddingValue> for Padding {
+    fn from(value: PaddingValue) -> Self {
+        match value {
+            PaddingValue::Uniform(size) => Self::uniform(size),
+            PaddingValue::Sides {
+                top,
+                right,
+                bottom,
+                left,
+            } => Self {
+                top,
+                right,
+                bottom,
+                left,
+            },
+        }
+    }
+}
+
+impl From<Padding> for PaddingValue {
+    fn from(padding: Padding) -> Self {
+        padding.as_uniform().map_or(
+            Self::Sides {
+                top: padding.top,
+                right: padding.right,
+                bottom: padding.bottom,
+                left: padding.left,
+            },
+            Self::Uniform,
+        )
+    }
+}
+
+impl JsonSchema for Padding {
+    fn schema_name() -> String {
+        String::from("Padding")
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        PaddingValue::json_schema(gen)
+    }
+}
+
+impl From<i32> for Padding {
+    fn from(size: i32) -> Self {
+        Self::uniform(size)
+    }
+}
+
+/// Parses either a single size for every side such as "10", or a size for each side in the
+/// order top, right, bottom, left such as "40,10,10,10"
+impl FromStr for Padding {
+    type Err = Report;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let sides = s
+            .split(',')
+            .map(|side| {
+                side.trim()
+                    .parse::<i32>()
+                    .map_err(|_| anyhow!("{side} is not a valid padding size"))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        match sides[..] {
+            [size] => Ok(Self::uniform(size)),
+            [top, right, bottom, left] => Ok(Self {
+                top,
+                right,
+                bottom,
+                left,
+            }),
+            _ => bail!(
+                "padding must be given as one size or as four sizes for top,right,bottom,left"
+            ),
+        }
+    }
+}
+
+impl Padding {
+    #[must_use]
+    pub const fn uniform(size: i32) -> Self {
+        Self {
+            top: size,
+            right: size,
+            bottom: size,
+            left: size,
+        }
+    }
+
+    /// The size of every side, if they are all the same
+    #[must_use]
+    pub const fn as_uniform(&self) -> Option<i32> {
+        if self.top == self.right && self.top == self.bottom && self.top == self.left {
+            Some(self.top)
+        } else {
+            None
+        }
+    }
+
+    /// Swaps the horizontal and vertical sides
+    #[must_use]
+    pub const fn transposed(&self) -> Self {
+        Self {
+            top: self.left,
+            right: self.bottom,
+            bottom: self.right,
+            left: self.top,
+        }
+    }
+
+    /// Adjusts every side by the same amount
+    #[must_use]
+    pub const fn adjusted_by(&self, sizing: Sizing, adjustment: i32) -> Self {
+        Self {
+            top: sizing.adjust_by(self.top, adjustment),
+            right: sizing.adjust_by(self.right, adjustment),
+            bottom: sizing.adjust_by(self.bottom, adjustment),
+            left: sizing.adjust_by(self.left, adjustment),
+        }
+    }
+}
@@ -0,0 +1,216 @@
+use std::num::NonZeroUsize;
+
+use clap::ValueEnum;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use strum::Display;
+use strum::EnumString;
+
+use crate::Axis;
+use crate::Layout;
+use crate::LayoutOptions;
+use crate::Padding;
+use crate::Rect;
+use crate::ResizeRatio;
+
+/// Colours cycled through for the containers of an SVG preview
+const SVG_COLOURS: [&str; 6] = [
+    "#89b4fa", "#a6e3a1", "#f9e2af", "#f38ba8", "#cba6f7", "#94e2d5",
+];
+
+#[derive(
+    Clone, Copy, Debug, Default, Serialize, Deserialize, Display, EnumString, ValueEnum, JsonSchema,
+)]
+#[strum(serialize_all = "snake_case")]
+pub enum PreviewFormat {
+    /// A diagram drawn with text characters
+    #[default]
+    Ascii,
+    /// An SVG image at the size of the layout area
+    Svg,
+}
+
+/// Everything needed to calculate a layout without a running window manager, so that layouts
+/// and custom layout files can be previewed while they are being written
+#[derive(Debug, Clone)]
+pub struct LayoutPreview {
+    pub layout: Layout,
+    pub area: Rect,
+    pub len: NonZeroUsize,
+    pub container_padding: Option<Padding>,
+    pub layout_flip: Option<Axis>,
+    pub layout_options: LayoutOptions,
+    pub resize_dimensions: Vec<Option<ResizeRatio>>,
+}
+
+impl LayoutPreview {
+    #[must_use]
+    pub fn layouts(&self) -> Vec<Rect> {
+        self.layout.as_boxed_arrangement().calculate(
+            &self.area,
+            self.len,
+            self.container_padding,
+            self.layout_flip,
+            self.layout_options,
+            0,
+            &self.resize_dimensions,
+        )
+    }
+
+    /// Renders the preview in a format, where ASCII diagrams are 80 characters wide
+    #[must_use]
+    pub fn render(&self, format: PreviewFormat) -> String {
+        match format {
+            PreviewFormat::Ascii => self.ascii(80),
+            PreviewFormat::Svg => self.svg(),
+        }
+    }
+
+    /// Draws every container as a box labelled with its index, where the number of rows is
+    /// chosen so that the diagram keeps the proportions of the area, as characters are roughly
+    /// twice as tall as they are wide
+    #[must_use]
+    #[allow(
+        clippy::cast_precision_loss,
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_possible_wrap
+    )]
+    pub fn ascii(&self, columns: usize) -> String {
+        let columns = columns.max(2);
+        let rows = if self.area.right > 0 {
+            ((columns as f32 * self.area.bottom as f32 / self.area.right as f32) / 2.0).round()
+                as usize
+        } else {
+            0
+        }
+        .max(2);
+
+        let scale = |offset: i32, total: i32, cells: usize| -> i64 {
+            if total <= 0 {
+                return 0;
+            }
+
+            (f64::from(offset) * (cells - 1) as f64 / f64::from(total)).round() as i64
+        };
+
+        let mut grid = vec![vec![' '; columns]; rows];
+
+        for (idx, layout) in self.layouts().iter().enumerate() {
+            let c0 = scale(layout.left - self.area.left, self.area.right, columns);
+            let c1 = scale(
+                layout.left + layout.right - self.area.left,
+                self.area.right,
+                columns,
+            );
+            let r0 = scale(layout.top - self.area.top, self.area.bottom, rows);
+            let r1 = scale(
+                layout.top + layout.bottom - self.area.top,
+                self.area.bottom,
+                rows,
+            );
+
+            for c in c0..=c1 {
+                draw(&mut grid, r0, c, '-');
+                draw(&mut grid, r1, c, '-');
+            }
+
+            for r in r0..=r1 {
+                draw(&mut grid, r, c0, '|');
+                draw(&mut grid, r, c1, '|');
+            }
+
+            // The index is only written when it fits inside of the box
+            let label = idx.to_string();
+            let label_len = label.len() as i64;
+            if c1 - c0 > label_len && r1 - r0 >= 2 {
+                let row = r0 + (r1 - r0) / 2;
+                let start = c0 + (c1 - c0) / 2 - (label_len - 1) / 2;
+
+                for (offset, ch) in label.chars().enumerate() {
+                    write(&mut grid, row, start + offset as i64, ch);
+                }
+            }
+        }
+
+        grid.iter()
+            .map(|row| row.iter().collect::<String>().trim_end().to_string())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Draws every container as a rectangle labelled with its index, on a canvas covering the
+    /// area of the layout
+    #[must_use]
+    pub fn svg(&self) -> String {
+        let area = &self.area;
+        let mut lines = vec![
+            format!(
+                r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{} {} {} {}" width="{}" height="{}">"#,
+                area.left, area.top, area.right, area.bottom, area.right, area.bottom
+            ),
+            format!(
+                r##"  <rect x="{}" y="{}" width="{}" height="{}" fill="#1e1e2e"/>"##,
+                area.left, area.top, area.right, area.bottom
+            ),
+        ];
+
+        for (idx, layout) in self.layouts().iter().enumerate() {
+            let colour = SVG_COLOURS[idx % SVG_COLOURS.len()];
+            let font_size = (layout.right.min(layout.bottom) / 3).max(1);
+
+            lines.push(format!(
+                r##"  <rect x="{}" y="{}" width="{}" height="{}" fill="{colour}" stroke="#11111b" stroke-width="2"/>"##,
+                layout.left,
+                layout.top,
+                layout.right.max(0),
+                layout.bottom.max(0)
+            ));
+
+            lines.push(format!(
+                r##"  <text x="{}" y="{}" font-family="monospace" font-size="{font_size}" text-anchor="middle" dominant-baseline="central" fill="#11111b">{idx}</text>"##,
+                layout.left + layout.right / 2,
+                layout.top + layout.bottom / 2,
+            ));
+        }
+
+        lines.push(String::from("</svg>"));
+        lines.join("\n")
+    }
+}
+
+/// Writes a character to a cell of the grid, ignoring cells which are out of bounds
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn write(grid: &mut [Vec<char>], row: i64, column: i64, ch: char) {
+    if row < 0 || column < 0 {
+        return;
+    }
+
+    if let Some(cell) = grid
+        .get_mut(row as usize)
+        .and_then(|cells| cells.get_mut(column as usize))
+    {
+        *cell = ch;
+    }
+}
+
+/// Draws part of a border, turning the places where borders cross into corners
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn draw(grid: &mut [Vec<char>], row: i64, column: i64, ch: char) {
+    if row < 0 || column < 0 {
+        return;
+    }
+
+    let existing = grid
+        .get(row as usize)
+        .and_then(|cells| cells.get(column as usize))
+        .copied();
+
+    let ch = match (existing, ch) {
+        (Some('-'), '|') | (Some('|'), '-') | (Some('+'), _) => '+',
+        _ => ch,
+    };
+
+    write(grid, row, column, ch);
+}
@@ -3,6 +3,8 @@ use serde::Deserialize;
 use serde::Serialize;
 use windows::Win32::Foundation::RECT;
 
+use crate::Padding;
+
 #[derive(Debug, Default, Clone, Copy, Serialize, Deserialize, Eq, PartialEq, JsonSchema)]
 pub struct Rect {
     /// The left point in a Win32 Rect
@@ -27,12 +29,23 @@ impl From<RECT> for Rect {
 }
 
 impl Rect {
-    pub fn add_padding(&mut self, padding: Option<i32>) {
+    pub const fn add_padding(&mut self, padding: Option<Padding>) {
         if let Some(padding) = padding {
-            self.left += padding;
-            self.top += padding;
-            self.right -= padding * 2;
-            self.bottom -= padding * 2;
+            self.left += padding.left;
+            self.top += padding.top;
+            self.right -= padding.left + padding.right;
+            self.bottom -= padding.top + padding.bottom;
+        }
+    }
+
+    /// Swaps the horizontal and vertical dimensions
+    #[must_use]
+    pub const fn transposed(&self) -> Self {
+        Self {
+            left: self.top,
+            top: self.left,
+            right: self.bottom,
+            bottom: self.right,
         }
     }
 
@@ -43,4 +56,40 @@ impl Rect {
             && point.1 >= self.top
             && point.1 <= self.top + self.bottom
     }
+
+    /// Whether any part of this Rect overlaps with another
+    #[must_use]
+    pub const fn intersects(&self, other: &Self) -> bool {
+        self.left < other.left + other.right
+            && other.left < self.left + self.right
+            && self.top < other.top + other.bottom
+            && other.top < self.top + self.bottom
+    }
+
+    /// Whether all of another Rect lies within this one
+    #[must_use]
+    pub const fn contains(&self, other: &Self) -> bool {
+        other.left >= self.left
+            && other.top >= self.top
+            && other.left + other.right <= self.left + self.right
+            && other.top + other.bottom <= self.top + self.bottom
+    }
+
+    /// Shrinks this Rect to fit within a maximum width and height, keeping it centered on the
+    /// area it originally covered
+    pub const fn constrain(&mut self, max_width: Option<i32>, max_height: Option<i32>) {
+        if let Some(max_width) = max_width {
+            if self.right > max_width {
+                self.left += (self.right - max_width) / 2;
+                self.right = max_width;
+            }
+        }
+
+        if let Some(max_height) = max_height {
+            if self.bottom > max_height {
+                self.top += (self.bottom - max_height) / 2;
+                self.bottom = max_height;
+            }
+        }
+    }
 }
@@ -0,0 +1,99 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::Rect;
+
+/// Resize adjustments to the edges of a container, stored as ratios of the area that a layout is
+/// calculated in so that the same proportions can be reproduced on an area of any size
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize, PartialEq, JsonSchema)]
+pub struct ResizeRatio {
+    /// Adjustment to the left edge as a ratio of the width of the area
+    pub left: f32,
+    /// Adjustment to the top edge as a ratio of the height of the area
+    pub top: f32,
+    /// Adjustment to the right edge as a ratio of the width of the area
+    pub right: f32,
+    /// Adjustment to the bottom edge as a ratio of the height of the area
+    pub bottom: f32,
+}
+
+impl ResizeRatio {
+    #[must_use]
+    pub fn from_pixels(resize: &Rect, area: &Rect) -> Self {
+        Self {
+            left: ratio(resize.left, area.right),
+            top: ratio(resize.top, area.bottom),
+            right: ratio(resize.right, area.right),
+            bottom: ratio(resize.bottom, area.bottom),
+        }
+    }
+
+    #[must_use]
+    pub fn to_pixels(&self, area: &Rect) -> Rect {
+        Rect {
+            left: pixels(self.left, area.right),
+            top: pixels(self.top, area.bottom),
+            right: pixels(self.right, area.right),
+            bottom: pixels(self.bottom, area.bottom),
+        }
+    }
+
+    /// Swaps the horizontal and vertical adjustments, for use with a transposed area
+    #[must_use]
+    pub const fn transposed(&self) -> Self {
+        Self {
+            left: self.top,
+            top: self.left,
+            right: self.bottom,
+            bottom: self.right,
+        }
+    }
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn ratio(delta: i32, total: i32) -> f32 {
+    if total == 0 {
+        0.0
+    } else {
+        delta as f32 / total as f32
+    }
+}
+
+#[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+fn pixels(ratio: f32, total: i32) -> i32 {
+    (ratio * total as f32).round() as i32
+}
+
+/// Converts resize ratios to pixel adjustments for a given area
+#[must_use]
+pub fn resize_pixels(resize_dimensions: &[Option<ResizeRatio>], area: &Rect) -> Vec<Option<Rect>> {
+    resize_dimensions
+        .iter()
+        .map(|resize| resize.map(|r| r.to_pixels(area)))
+        .collect()
+}
+
+/// Resize dimensions as read from a file written by `Save` or `QuickSave`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum SavedResizeDimensions {
+    // Legacy files store pixel adjustments as integers, which serde_json will not read from the
+    // floats written for ratios, so this variant has to be tried first
+    Pixels(Vec<Option<Rect>>),
+    Ratios(Vec<Option<ResizeRatio>>),
+}
+
+impl SavedResizeDimensions {
+    /// Legacy pixel adjustments are converted relative to the area they are being loaded into
+    #[must_use]
+    pub fn into_ratios(self, area: &Rect) -> Vec<Option<ResizeRatio>> {
+        match self {
+            Self::Pixels(pixels) => pixels
+                .iter()
+                .map(|resize| resize.map(|r| ResizeRatio::from_pixels(&r, area)))
+                .collect(),
+            Self::Ratios(ratios) => ratios,
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use crate::arrangement::flip_layouts;
+use crate::resize_ratio::resize_pixels;
+use crate::Axis;
+use crate::LayoutOptions;
+use crate::Rect;
+use crate::ResizeRatio;
+
+/// The width of every column on the strip, where moving either edge of a column only changes the
+/// width of that column, as columns never need to share the width of the work area
+#[allow(
+    clippy::cast_precision_loss,
+    clippy::cast_possible_truncation,
+    clippy::cast_possible_wrap
+)]
+fn column_widths(
+    area: &Rect,
+    len: usize,
+    layout_options: LayoutOptions,
+    resize_dimensions: &[Option<Rect>],
+) -> Vec<i32> {
+    let width = layout_options.column_width(area.right);
+    let min_width = (area.right as f32 * LayoutOptions::MIN_COLUMN_RATIO) as i32;
+
+    (0..len)
+        .map(|idx| {
+            let adjustment = resize_dimensions
+                .get(idx)
+                .copied()
+                .flatten()
+                .map_or(0, |resize| resize.right - resize.left);
+
+            (width + adjustment).clamp(min_width, area.right.max(min_width))
+        })
+        .collect()
+}
+
+/// Lays out containers as columns on a strip which starts at the scroll offset of the viewport,
+/// so any columns which don't fit in the work area are placed partially or fully outside of it
+#[must_use]
+pub fn scrolling(
+    area: &Rect,
+    len: usize,
+    layout_flip: Option<Axis>,
+    layout_options: LayoutOptions,
+    scroll_offset: i32,
+    resize_dimensions: &[Option<Rect>],
+) -> Vec<Rect> {
+    let mut left = area.left - scroll_offset;
+
+    let mut layouts = column_widths(area, len, layout_options, resize_dimensions)
+        .into_iter()
+        .map(|width| {
+            let layout = Rect {
+                left,
+                top: area.top,
+                right: width,
+                bottom: area.bottom,
+            };
+
+            left += width;
+            layout
+        })
+        .collect::<Vec<_>>();
+
+    flip_layouts(&mut layouts, area, layout_flip);
+
+    layouts
+}
+
+/// The scroll offset which moves the viewport as little as possible from `current` to bring the
+/// column at `idx` fully into view, without scrolling past either end of the strip
+#[must_use]
+pub fn scroll_offset(
+    area: &Rect,
+    idx: usize,
+    len: usize,
+    layout_options: LayoutOptions,
+    current: i32,
+    resize_dimensions: &[Option<ResizeRatio>],
+) -> i32 {
+    let widths = column_widths(
+        area,
+        len,
+        layout_options,
+        &resize_pixels(resize_dimensions, area),
+    );
+    let start = widths.iter().take(idx).sum::<i32>();
+    let end = start + widths.get(idx).copied().unwrap_or_default();
+    let total = widths.iter().sum::<i32>();
+
+    current
+        .min(start)
+        .max(end - area.right)
+        .min(total - area.right)
+        .max(0)
+}
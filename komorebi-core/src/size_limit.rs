@@ -0,0 +1,75 @@
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::str::FromStr;
+
+use color_eyre::eyre::anyhow;
+use color_eyre::eyre::bail;
+use color_eyre::Report;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// A limit on the size of a container, given either in pixels or relative to the work area
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, JsonSchema)]
+pub enum SizeLimit {
+    /// A fixed number of pixels
+    Pixels(i32),
+    /// A percentage of the work area, between 0 and 100
+    Percentage(f32),
+}
+
+impl SizeLimit {
+    /// The limit in pixels for a work area dimension of the given size
+    #[must_use]
+    #[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+    pub fn resolve(self, total: i32) -> i32 {
+        match self {
+            Self::Pixels(pixels) => pixels,
+            Self::Percentage(percentage) => (total as f32 * percentage / 100.0).round() as i32,
+        }
+    }
+}
+
+impl Display for SizeLimit {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Pixels(pixels) => write!(f, "{pixels}"),
+            Self::Percentage(percentage) => write!(f, "{percentage}%"),
+        }
+    }
+}
+
+/// Parses either a number of pixels such as "1920" or a percentage such as "60%"
+impl FromStr for SizeLimit {
+    type Err = Report;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+
+        if let Some(percentage) = s.strip_suffix('%') {
+            let percentage = percentage
+                .trim()
+                .parse::<f32>()
+                .map_err(|_| anyhow!("{s} is not a valid percentage"))?;
+
+            if percentage <= 0.0 || percentage > 100.0 {
+                bail!("percentages must be greater than 0 and at most 100");
+            }
+
+            return Ok(Self::Percentage(percentage));
+        }
+
+        let pixels = s
+            .strip_suffix("px")
+            .unwrap_or(s)
+            .trim()
+            .parse::<i32>()
+            .map_err(|_| anyhow!("{s} is not a valid number of pixels or percentage"))?;
+
+        if pixels <= 0 {
+            bail!("pixel limits must be greater than 0");
+        }
+
+        Ok(Self::Pixels(pixels))
+    }
+}
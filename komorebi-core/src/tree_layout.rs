@@ -0,0 +1,345 @@
+use std::path::Path;
+
+use color_eyre::Result;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::arrangement::columns;
+use crate::arrangement::rows;
+use crate::custom_layout::deserialize_file;
+use crate::direction::Geometric;
+use crate::direction::NAVIGATION_AREA;
+use crate::layout_validation::ensure_valid;
+use crate::layout_validation::LayoutError;
+use crate::layout_validation::LayoutErrorKind;
+use crate::OperationDirection;
+use crate::Rect;
+
+/// A custom layout described as a tree of nested splits, where every leaf holds a fixed number of
+/// containers, apart from a single overflow leaf which holds all of the remaining containers
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(transparent)]
+pub struct TreeLayout {
+    root: TreeNode,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct TreeNode {
+    /// The axis along which the children or containers of this node are laid out
+    /// (default: Horizontal)
+    #[serde(default)]
+    pub axis: SplitAxis,
+    /// The share of the parent node given to this node, between 0 and 1
+    /// (default: an equal share of the remaining space)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ratio: Option<f32>,
+    /// The nodes that this node is split into, leave empty for a leaf
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<TreeNode>,
+    /// The maximum number of containers in this leaf (default: 1)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capacity: Option<usize>,
+    /// Whether this leaf holds every container that does not fit in the other leaves
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub overflow: bool,
+}
+
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub enum SplitAxis {
+    /// Side by side, from left to right
+    #[default]
+    Horizontal,
+    /// Stacked, from top to bottom
+    Vertical,
+}
+
+impl TreeLayout {
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let layout: Self = deserialize_file(path.as_ref())?;
+        ensure_valid(&layout.validate())?;
+
+        Ok(layout)
+    }
+
+    #[must_use]
+    pub const fn root(&self) -> &TreeNode {
+        &self.root
+    }
+
+    #[must_use]
+    pub fn is_valid(&self) -> bool {
+        self.validate().is_empty()
+    }
+
+    /// Every problem which makes this layout invalid, with the node that each was found in
+    #[must_use]
+    pub fn validate(&self) -> Vec<LayoutError> {
+        let mut errors = vec![];
+        let mut overflow_leaves = 0;
+        self.root
+            .validate("root", &mut overflow_leaves, &mut errors);
+
+        // There must be exactly one leaf for containers which don't fit anywhere else
+        if overflow_leaves == 0 {
+            errors.push(LayoutError::new("root", LayoutErrorKind::MissingOverflow));
+        }
+
+        errors
+    }
+
+    /// The number of containers placed in each leaf, in the order that the leaves appear
+    #[must_use]
+    pub fn leaf_container_counts(&self, len: usize) -> Vec<usize> {
+        let mut leaves = vec![];
+        self.root.leaves(&mut leaves);
+
+        let capacity = leaves
+            .iter()
+            .filter(|leaf| !leaf.overflow)
+            .map(|leaf| leaf.leaf_capacity())
+            .sum::<usize>();
+
+        // Leaves are filled in order, and the overflow leaf only takes containers once every
+        // other leaf is full, so that no space is given to an empty leaf
+        let mut remaining = len;
+        leaves
+            .iter()
+            .map(|leaf| {
+                let count = if leaf.overflow {
+                    len.saturating_sub(capacity)
+                } else {
+                    leaf.leaf_capacity()
+                }
+                .min(remaining);
+
+                remaining -= count;
+                count
+            })
+            .collect()
+    }
+
+    /// Calculates the unpadded, unflipped area of every container in the tree
+    #[must_use]
+    pub fn layouts(&self, area: &Rect, len: usize) -> Vec<Rect> {
+        let mut layouts = vec![];
+        self.root
+            .layout(area, &self.leaf_container_counts(len), &mut layouts);
+
+        layouts
+    }
+
+    /// Finds the container in a direction from the positions of the containers in an unflipped
+    /// layout, as the containers of a tree can't be navigated with index arithmetic
+    #[must_use]
+    pub fn neighbour(
+        &self,
+        op_direction: OperationDirection,
+        idx: usize,
+        count: usize,
+    ) -> Option<usize> {
+        let layouts = self.layouts(&NAVIGATION_AREA, count);
+
+        Geometric {
+            layouts: &layouts,
+            focus_history: &[],
+        }
+        .neighbour(op_direction, idx)
+    }
+}
+
+impl TreeNode {
+    const fn is_leaf(&self) -> bool {
+        self.children.is_empty()
+    }
+
+    fn leaf_capacity(&self) -> usize {
+        self.capacity.unwrap_or(1)
+    }
+
+    fn validate(&self, location: &str, overflow_leaves: &mut usize, errors: &mut Vec<LayoutError>) {
+        if self.is_leaf() {
+            if self.overflow {
+                *overflow_leaves += 1;
+                if *overflow_leaves > 1 {
+                    errors.push(LayoutError::new(
+                        location,
+                        LayoutErrorKind::MultipleOverflows,
+                    ));
+                }
+
+                // The overflow leaf can't have a fixed capacity
+                if self.capacity.is_some() {
+                    errors.push(LayoutError::new(
+                        location,
+                        LayoutErrorKind::OverflowCapacity,
+                    ));
+                }
+            } else if self.leaf_capacity() == 0 {
+                // Every other leaf must have room for at least one container
+                errors.push(LayoutError::new(location, LayoutErrorKind::EmptyLeaf));
+            }
+
+            return;
+        }
+
+        if self.overflow {
+            errors.push(LayoutError::new(location, LayoutErrorKind::OverflowBranch));
+        }
+
+        if self.capacity.is_some() {
+            errors.push(LayoutError::new(location, LayoutErrorKind::BranchCapacity));
+        }
+
+        let mut total_ratio = 0.0;
+        let mut unconfigured = 0;
+
+        for (idx, child) in self.children.iter().enumerate() {
+            let child_location = format!("{location}.children[{idx}]");
+
+            match child.ratio {
+                Some(ratio) => {
+                    // Every ratio must leave some space for the other children
+                    if ratio <= 0.0 || ratio >= 1.0 {
+                        errors.push(LayoutError::new(
+                            format!("{child_location}.ratio"),
+                            LayoutErrorKind::BadRatio(ratio),
+                        ));
+                    }
+
+                    total_ratio += ratio;
+                }
+                None => unconfigured += 1,
+            }
+
+            child.validate(&child_location, overflow_leaves, errors);
+        }
+
+        let ratios_fit = if unconfigured == 0 {
+            total_ratio <= 1.0
+        } else {
+            total_ratio < 1.0
+        };
+
+        if !ratios_fit {
+            errors.push(LayoutError::new(
+                location,
+                LayoutErrorKind::RatiosExceedArea(total_ratio),
+            ));
+        }
+    }
+
+    fn leaves<'a>(&'a self, leaves: &mut Vec<&'a Self>) {
+        if self.is_leaf() {
+            leaves.push(self);
+        } else {
+            for child in &self.children {
+                child.leaves(leaves);
+            }
+        }
+    }
+
+    fn leaf_count(&self) -> usize {
+        if self.is_leaf() {
+            1
+        } else {
+            self.children.iter().map(Self::leaf_count).sum()
+        }
+    }
+
+    fn layout(&self, area: &Rect, counts: &[usize], layouts: &mut Vec<Rect>) {
+        if self.is_leaf() {
+            let count = counts.first().copied().unwrap_or_default();
+
+            if count > 0 {
+                layouts.append(&mut match self.axis {
+                    SplitAxis::Horizontal => columns(area, count),
+                    SplitAxis::Vertical => rows(area, count),
+                });
+            }
+
+            return;
+        }
+
+        // Children without any containers are skipped so that their space is given to the others
+        let mut active = vec![];
+        let mut offset = 0;
+
+        for child in &self.children {
+            let leaf_count = child.leaf_count();
+            let child_counts =
+                &counts[offset.min(counts.len())..(offset + leaf_count).min(counts.len())];
+            offset += leaf_count;
+
+            if child_counts.iter().sum::<usize>() > 0 {
+                active.push((child, child_counts));
+            }
+        }
+
+        let total = match self.axis {
+            SplitAxis::Horizontal => area.right,
+            SplitAxis::Vertical => area.bottom,
+        };
+
+        let mut start = 0;
+
+        for ((child, child_counts), end) in active.iter().zip(split_positions(
+            active.iter().map(|(child, _)| child.ratio),
+            total,
+        )) {
+            let child_area = match self.axis {
+                SplitAxis::Horizontal => Rect {
+                    left: area.left + start,
+                    right: end - start,
+                    ..*area
+                },
+                SplitAxis::Vertical => Rect {
+                    top: area.top + start,
+                    bottom: end - start,
+                    ..*area
+                },
+            };
+
+            child.layout(&child_area, child_counts, layouts);
+            start = end;
+        }
+    }
+}
+
+/// The position of the far edge of each share of a total, where shares without a configured ratio
+/// split whatever is left equally, and all shares are scaled so that they fill the total
+#[allow(
+    clippy::cast_precision_loss,
+    clippy::cast_possible_truncation,
+    clippy::cast_possible_wrap
+)]
+fn split_positions(ratios: impl Iterator<Item = Option<f32>> + Clone, total: i32) -> Vec<i32> {
+    let configured = ratios.clone().flatten().sum::<f32>();
+    let unconfigured = ratios.clone().filter(Option::is_none).count();
+
+    let equal_share = if unconfigured == 0 {
+        0.0
+    } else {
+        (1.0 - configured).max(0.0) / unconfigured as f32
+    };
+
+    let shares = ratios
+        .map(|ratio| ratio.unwrap_or(equal_share))
+        .collect::<Vec<_>>();
+    let sum = shares.iter().sum::<f32>();
+
+    let mut accumulated = 0.0;
+    shares
+        .iter()
+        .map(|share| {
+            accumulated += share;
+
+            if sum > 0.0 {
+                (accumulated / sum * total as f32).round() as i32
+            } else {
+                total
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,46 @@
+use std::collections::VecDeque;
+
+use crate::monitor::Monitor;
+use crate::ring::Ring;
+
+/// The number of arrangements which are kept to be undone
+const HISTORY_LIMIT: usize = 50;
+
+/// The arrangements of monitors, workspaces and containers from before each command which changed
+/// them, so that the commands can be undone and redone
+#[derive(Debug, Default)]
+pub struct History {
+    undo: VecDeque<Ring<Monitor>>,
+    redo: Vec<Ring<Monitor>>,
+}
+
+impl History {
+    /// Records the arrangement from before a command, dropping the oldest arrangement once the
+    /// limit is reached and anything that could have been redone
+    pub fn record(&mut self, monitors: Ring<Monitor>) {
+        self.undo.push_back(monitors);
+        if self.undo.len() > HISTORY_LIMIT {
+            self.undo.pop_front();
+        }
+
+        self.redo.clear();
+    }
+
+    /// Takes the arrangement to go back to, keeping the current arrangement so that it can be
+    /// redone
+    pub fn undo(&mut self, current: &Ring<Monitor>) -> Option<Ring<Monitor>> {
+        let previous = self.undo.pop_back()?;
+        self.redo.push(current.clone());
+
+        Option::from(previous)
+    }
+
+    /// Takes the arrangement that was last undone, keeping the current arrangement so that it can
+    /// be undone again
+    pub fn redo(&mut self, current: &Ring<Monitor>) -> Option<Ring<Monitor>> {
+        let next = self.redo.pop()?;
+        self.undo.push_back(current.clone());
+
+        Option::from(next)
+    }
+}
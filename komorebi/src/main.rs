@@ -18,7 +18,6 @@ use std::sync::atomic::AtomicIsize;
 use std::sync::atomic::AtomicU32;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
-#[cfg(feature = "deadlock_detection")]
 use std::time::Duration;
 
 use clap::Parser;
@@ -46,7 +45,9 @@ use crate::hidden::Hidden;
 use komorebi_core::config_generation::IdWithIdentifier;
 use komorebi_core::config_generation::MatchingStrategy;
 use komorebi_core::ApplicationIdentifier;
+use komorebi_core::ContainerConstraints;
 use komorebi_core::HidingBehaviour;
+use komorebi_core::Padding;
 use komorebi_core::Rect;
 use komorebi_core::SocketMessage;
 
@@ -54,6 +55,7 @@ use crate::process_command::listen_for_commands;
 use crate::process_command::listen_for_commands_tcp;
 use crate::process_event::listen_for_events;
 use crate::process_movement::listen_for_movements;
+use crate::snapshot::Snapshot;
 use crate::static_config::StaticConfig;
 use crate::window_manager::State;
 use crate::window_manager::WindowManager;
@@ -67,11 +69,15 @@ mod border;
 mod com;
 mod container;
 mod hidden;
+mod history;
+mod marks;
 mod monitor;
 mod process_command;
 mod process_event;
 mod process_movement;
+mod scratchpad;
 mod set_window_position;
+mod snapshot;
 mod static_config;
 mod styles;
 mod window;
@@ -148,6 +154,11 @@ lazy_static! {
     static ref REGEX_IDENTIFIERS: Arc<Mutex<HashMap<String, Regex>>> =
         Arc::new(Mutex::new(HashMap::new()));
     static ref MANAGE_IDENTIFIERS: Arc<Mutex<Vec<IdWithIdentifier>>> = Arc::new(Mutex::new(vec![]));
+    static ref SCRATCHPAD_IDENTIFIERS: Arc<Mutex<Vec<IdWithIdentifier>>> = Arc::new(Mutex::new(vec![]));
+    static ref SIZE_CONSTRAINT_RULES: Arc<Mutex<Vec<(IdWithIdentifier, ContainerConstraints)>>> =
+        Arc::new(Mutex::new(vec![]));
+    static ref SIZE_CONSTRAINTS: Arc<Mutex<HashMap<isize, Option<ContainerConstraints>>>> =
+        Arc::new(Mutex::new(HashMap::new()));
     static ref FLOAT_IDENTIFIERS: Arc<Mutex<Vec<IdWithIdentifier>>> = Arc::new(Mutex::new(vec![
         // mstsc.exe creates these on Windows 11 when a WSL process is launched
         // https://github.com/LGUG2Z/komorebi/issues/74
@@ -217,10 +228,13 @@ lazy_static! {
     // Use app-specific titlebar removal options where possible
     // eg. Windows Terminal, IntelliJ IDEA, Firefox
     static ref NO_TITLEBAR: Arc<Mutex<Vec<String>>> = Arc::new(Mutex::new(vec![]));
-}
 
-pub static DEFAULT_WORKSPACE_PADDING: AtomicI32 = AtomicI32::new(10);
-pub static DEFAULT_CONTAINER_PADDING: AtomicI32 = AtomicI32::new(10);
+    static ref DEFAULT_WORKSPACE_PADDING: Arc<Mutex<Padding>> =
+        Arc::new(Mutex::new(Padding::uniform(10)));
+
+    static ref DEFAULT_CONTAINER_PADDING: Arc<Mutex<Padding>> =
+        Arc::new(Mutex::new(Padding::uniform(10)));
+}
 
 pub static INITIAL_CONFIGURATION_LOADED: AtomicBool = AtomicBool::new(false);
 pub static CUSTOM_FFM: AtomicBool = AtomicBool::new(false);
@@ -444,6 +458,39 @@ fn detect_deadlocks() {
     });
 }
 
+fn persist_state(wm: Arc<Mutex<WindowManager>>) {
+    // Create a background thread which persists the window manager state every 30s, so that
+    // workspace assignments survive komorebi being killed or crashing
+    std::thread::spawn(move || loop {
+        std::thread::sleep(Duration::from_secs(30));
+        Snapshot::persist(&wm.lock());
+    });
+}
+
+/// Restores the window manager state persisted by a previous komorebi process, returning whether
+/// there was any state to restore
+fn restore_persisted_state(wm: &mut WindowManager) -> bool {
+    let path = Snapshot::state_path();
+    if !path.is_file() {
+        return false;
+    }
+
+    tracing::info!("restoring window manager state from {}", path.display());
+
+    let restored = Snapshot::load_from(&path).and_then(|snapshot| {
+        snapshot.adopt_hidden_windows(wm)?;
+        snapshot.restore(wm)
+    });
+
+    match restored {
+        Ok(()) => true,
+        Err(error) => {
+            tracing::warn!("could not restore the window manager state: {error}");
+            false
+        }
+    }
+}
+
 #[derive(Parser)]
 #[clap(author, about, version)]
 struct Opts {
@@ -459,6 +506,10 @@ struct Opts {
     /// Path to a static configuration JSON file
     #[clap(short, long)]
     config: Option<PathBuf>,
+    /// Ignore the window manager state persisted by the last komorebi process and tile every
+    /// window from scratch
+    #[clap(long)]
+    clean_state: bool,
 }
 
 #[tracing::instrument]
@@ -566,8 +617,11 @@ fn main() -> Result<()> {
         }
     }
 
-    wm.lock().retile_all(false)?;
+    if opts.clean_state || !restore_persisted_state(&mut wm.lock()) {
+        wm.lock().retile_all(false)?;
+    }
 
+    persist_state(wm.clone());
     listen_for_events(wm.clone());
 
     if CUSTOM_FFM.load(Ordering::SeqCst) {
@@ -587,6 +641,7 @@ fn main() -> Result<()> {
 
     tracing::error!("received ctrl-c, restoring all hidden windows and terminating process");
 
+    Snapshot::persist(&wm.lock());
     wm.lock().restore_all_windows()?;
 
     if WindowsApi::focus_follows_mouse()? {
@@ -0,0 +1,105 @@
+use std::collections::HashMap;
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+/// Labels attached to windows, which are looked up by handle because a handle stays the same
+/// while its window is moved between containers, workspaces, monitors and the scratchpad
+#[derive(Debug, Clone, Default, Serialize, JsonSchema)]
+pub struct Marks(HashMap<String, isize>);
+
+impl Marks {
+    /// Attaches a label to a window, taking it off of any window that it was attached to before
+    pub fn mark(&mut self, mark: String, hwnd: isize) {
+        self.0.insert(mark, hwnd);
+    }
+
+    pub fn unmark(&mut self, mark: &str) -> Option<isize> {
+        self.0.remove(mark)
+    }
+
+    pub fn window(&self, mark: &str) -> Option<isize> {
+        self.0.get(mark).copied()
+    }
+
+    /// Takes every label off of a window which is no longer managed
+    pub fn remove_window(&mut self, hwnd: isize) {
+        self.0.retain(|_, marked| *marked != hwnd);
+    }
+
+    /// Takes the labels off of windows which have been closed or unmanaged without an event for
+    /// them, so that a label is never left on a handle which could be reused by another window
+    pub fn retain_managed(&mut self, is_managed: impl Fn(isize) -> bool) {
+        self.0.retain(|_, marked| is_managed(*marked));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::container::Container;
+    use crate::window::Window;
+    use crate::workspace::Workspace;
+
+    fn workspace_with_windows(hwnds: &[isize]) -> Workspace {
+        let mut workspace = Workspace::default();
+        for hwnd in hwnds {
+            let mut container = Container::default();
+            container.add_window(Window { hwnd: *hwnd });
+            workspace.add_container(container);
+        }
+
+        workspace
+    }
+
+    #[test]
+    fn marks_follow_windows_between_workspaces() {
+        let mut workspaces = [
+            workspace_with_windows(&[1, 2]),
+            workspace_with_windows(&[3]),
+        ];
+
+        let mut marks = Marks::default();
+        marks.mark(String::from("editor"), 2);
+        marks.mark(String::from("terminal"), 3);
+
+        let container = workspaces[0]
+            .remove_focused_container()
+            .expect("there should be a focused container");
+        workspaces[1].add_container(container);
+
+        marks.retain_managed(|hwnd| {
+            workspaces
+                .iter()
+                .any(|workspace| workspace.contains_window(hwnd))
+        });
+
+        assert_eq!(marks.window("editor"), Some(2));
+        assert_eq!(marks.window("terminal"), Some(3));
+        assert!(workspaces[1].contains_window(2));
+        assert!(!workspaces[0].contains_window(2));
+    }
+
+    #[test]
+    fn marks_are_removed_with_their_windows() {
+        let mut workspace = workspace_with_windows(&[1, 2]);
+
+        let mut marks = Marks::default();
+        marks.mark(String::from("editor"), 1);
+        marks.mark(String::from("browser"), 2);
+        marks.mark(String::from("other"), 2);
+
+        workspace
+            .remove_window(2)
+            .expect("the window should be removed");
+        marks.retain_managed(|hwnd| workspace.contains_window(hwnd));
+
+        assert_eq!(marks.window("editor"), Some(1));
+        assert_eq!(marks.window("browser"), None);
+        assert_eq!(marks.window("other"), None);
+
+        marks.remove_window(1);
+        assert_eq!(marks.window("editor"), None);
+    }
+}
@@ -11,6 +11,7 @@ use getset::Setters;
 use schemars::JsonSchema;
 use serde::Serialize;
 
+use komorebi_core::LayoutFinding;
 use komorebi_core::Rect;
 
 use crate::container::Container;
@@ -63,6 +64,15 @@ pub fn new(id: isize, size: Rect, work_area_size: Rect, name: String) -> Monitor
 }
 
 impl Monitor {
+    /// The name, device and device id of this monitor, any of which can be used to match it
+    pub fn identifiers(&self) -> Vec<String> {
+        let mut identifiers = vec![self.name.clone()];
+        identifiers.extend(self.device.iter().cloned());
+        identifiers.extend(self.device_id.iter().cloned());
+
+        identifiers
+    }
+
     pub fn load_focused_workspace(&mut self, mouse_follows_focus: bool) -> Result<()> {
         let focused_idx = self.focused_workspace_idx();
         for (i, workspace) in self.workspaces_mut().iter_mut().enumerate() {
@@ -196,6 +206,7 @@ impl Monitor {
         invisible_borders: &Rect,
     ) -> Result<()> {
         let work_area = *self.work_area_size();
+        let identifiers = self.identifiers();
         let offset = if self.work_area_offset().is_some() {
             self.work_area_offset()
         } else {
@@ -204,8 +215,27 @@ impl Monitor {
 
         self.focused_workspace_mut()
             .ok_or_else(|| anyhow!("there is no workspace"))?
-            .update(&work_area, offset, invisible_borders)?;
+            .update(&work_area, offset, invisible_borders, &identifiers)?;
 
         Ok(())
     }
+
+    /// Checks the layout last applied to the focused workspace against the work area it is laid
+    /// out in
+    pub fn check_focused_workspace_layout(
+        &self,
+        offset: Option<Rect>,
+    ) -> Result<Vec<LayoutFinding>> {
+        let offset = if self.work_area_offset().is_some() {
+            self.work_area_offset()
+        } else {
+            offset
+        };
+
+        let workspace = self
+            .focused_workspace()
+            .ok_or_else(|| anyhow!("there is no workspace"))?;
+
+        Ok(workspace.check_layout(self.work_area_size(), offset, workspace.latest_layout()))
+    }
 }
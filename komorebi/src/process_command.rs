@@ -24,14 +24,16 @@ use uds_windows::UnixStream;
 use komorebi_core::config_generation::ApplicationConfiguration;
 use komorebi_core::config_generation::IdWithIdentifier;
 use komorebi_core::config_generation::MatchingStrategy;
+use komorebi_core::custom_layout::Orientation;
+use komorebi_core::resize_ratio::SavedResizeDimensions;
 use komorebi_core::ApplicationIdentifier;
 use komorebi_core::Axis;
+use komorebi_core::CustomLayout;
 use komorebi_core::FocusFollowsMouseImplementation;
 use komorebi_core::Layout;
 use komorebi_core::MoveBehaviour;
 use komorebi_core::OperationDirection;
 use komorebi_core::Rect;
-use komorebi_core::Sizing;
 use komorebi_core::SocketMessage;
 use komorebi_core::StateQuery;
 use komorebi_core::WindowContainerBehaviour;
@@ -40,6 +42,7 @@ use komorebi_core::WindowKind;
 use crate::border::Border;
 use crate::current_virtual_desktop;
 use crate::notify_subscribers;
+use crate::snapshot::Snapshot;
 use crate::static_config::StaticConfig;
 use crate::window::Window;
 use crate::window_manager;
@@ -70,6 +73,7 @@ use crate::MONITOR_INDEX_PREFERENCES;
 use crate::NO_TITLEBAR;
 use crate::OBJECT_NAME_CHANGE_ON_LAUNCH;
 use crate::REMOVE_TITLEBARS;
+use crate::SCRATCHPAD_IDENTIFIERS;
 use crate::SUBSCRIPTION_PIPES;
 use crate::TCP_CONNECTIONS;
 use crate::TRAY_AND_MULTI_WINDOW_IDENTIFIERS;
@@ -172,6 +176,8 @@ impl WindowManager {
             _ => {}
         };
 
+        let previous_arrangement = message.changes_arrangement().then(|| self.monitors.clone());
+
         match message {
             SocketMessage::CycleFocusWorkspace(_) | SocketMessage::FocusWorkspaceNumber(_) => {
                 if let Some(monitor) = self.focused_monitor_mut() {
@@ -220,6 +226,13 @@ impl WindowManager {
             SocketMessage::ToggleFloat => self.toggle_float()?,
             SocketMessage::ToggleMonocle => self.toggle_monocle()?,
             SocketMessage::ToggleMaximize => self.toggle_maximize()?,
+            SocketMessage::SendToScratchpad(ref name) => self.send_to_scratchpad(name.clone())?,
+            SocketMessage::ToggleScratchpad(ref name) => self.toggle_scratchpad(name)?,
+            SocketMessage::CycleScratchpad(direction) => self.cycle_scratchpad(direction)?,
+            SocketMessage::Mark(ref mark) => self.mark_focused_window(mark.clone())?,
+            SocketMessage::FocusMark(ref mark) => self.focus_mark(mark)?,
+            SocketMessage::SwapWithMark(ref mark) => self.swap_with_mark(mark)?,
+            SocketMessage::Unmark(ref mark) => self.unmark(mark)?,
             SocketMessage::ContainerPadding(monitor_idx, workspace_idx, size) => {
                 self.set_container_padding(monitor_idx, workspace_idx, size)?;
             }
@@ -278,6 +291,24 @@ impl WindowManager {
                     });
                 }
             }
+            SocketMessage::ScratchpadRule(identifier, ref id) => {
+                let mut scratchpad_identifiers = SCRATCHPAD_IDENTIFIERS.lock();
+
+                let mut should_push = true;
+                for s in &*scratchpad_identifiers {
+                    if s.id.eq(id) {
+                        should_push = false;
+                    }
+                }
+
+                if should_push {
+                    scratchpad_identifiers.push(IdWithIdentifier {
+                        kind: identifier,
+                        id: id.clone(),
+                        matching_strategy: Option::from(MatchingStrategy::Legacy),
+                    });
+                }
+            }
             SocketMessage::FloatRule(identifier, ref id) => {
                 let mut float_identifiers = FLOAT_IDENTIFIERS.lock();
 
@@ -370,6 +401,15 @@ impl WindowManager {
             SocketMessage::AdjustWorkspacePadding(sizing, adjustment) => {
                 self.adjust_workspace_padding(sizing, adjustment)?;
             }
+            SocketMessage::AdjustMainRatio(sizing, adjustment) => {
+                self.adjust_main_ratio(sizing, adjustment)?;
+            }
+            SocketMessage::AdjustMasterCount(sizing, adjustment) => {
+                self.adjust_master_count(sizing, adjustment)?;
+            }
+            SocketMessage::AdjustColumnRatio(sizing, adjustment) => {
+                self.adjust_column_ratio(sizing, adjustment)?;
+            }
             SocketMessage::MoveContainerToWorkspaceNumber(workspace_idx) => {
                 self.move_container_to_workspace(workspace_idx, true)?;
             }
@@ -488,6 +528,7 @@ impl WindowManager {
                 self.update_focused_workspace(self.mouse_follows_focus)?;
             }
             SocketMessage::Retile => self.retile_all(false)?,
+            SocketMessage::Balance(scope) => self.balance(scope)?,
             SocketMessage::FlipLayout(layout_flip) => self.flip_layout(layout_flip)?,
             SocketMessage::ChangeLayout(layout) => self.change_workspace_layout_default(layout)?,
             SocketMessage::CycleLayout(direction) => self.cycle_layout(direction)?,
@@ -500,6 +541,23 @@ impl WindowManager {
             SocketMessage::WorkspaceTiling(monitor_idx, workspace_idx, tile) => {
                 self.set_workspace_tiling(monitor_idx, workspace_idx, tile)?;
             }
+            SocketMessage::WorkspaceDirectionStrategy(
+                monitor_idx,
+                workspace_idx,
+                direction_strategy,
+            ) => {
+                self.set_workspace_direction_strategy(
+                    monitor_idx,
+                    workspace_idx,
+                    direction_strategy,
+                )?;
+            }
+            SocketMessage::WorkspaceBspStrategy(monitor_idx, workspace_idx, bsp_strategy) => {
+                self.set_workspace_bsp_strategy(monitor_idx, workspace_idx, bsp_strategy)?;
+            }
+            SocketMessage::WorkspaceMaxSize(monitor_idx, workspace_idx, max_width, max_height) => {
+                self.set_workspace_max_size(monitor_idx, workspace_idx, max_width, max_height)?;
+            }
             SocketMessage::WorkspaceLayout(monitor_idx, workspace_idx, layout) => {
                 self.set_workspace_layout_default(monitor_idx, workspace_idx, layout)?;
             }
@@ -546,6 +604,31 @@ impl WindowManager {
                     self.set_workspace_tiling(monitor_idx, workspace_idx, tile)?;
                 }
             }
+            SocketMessage::NamedWorkspaceBspStrategy(ref workspace, bsp_strategy) => {
+                if let Some((monitor_idx, workspace_idx)) =
+                    self.monitor_workspace_index_by_name(workspace)
+                {
+                    self.set_workspace_bsp_strategy(monitor_idx, workspace_idx, bsp_strategy)?;
+                }
+            }
+            SocketMessage::NamedWorkspaceMaxSize(ref workspace, max_width, max_height) => {
+                if let Some((monitor_idx, workspace_idx)) =
+                    self.monitor_workspace_index_by_name(workspace)
+                {
+                    self.set_workspace_max_size(monitor_idx, workspace_idx, max_width, max_height)?;
+                }
+            }
+            SocketMessage::NamedWorkspaceDirectionStrategy(ref workspace, direction_strategy) => {
+                if let Some((monitor_idx, workspace_idx)) =
+                    self.monitor_workspace_index_by_name(workspace)
+                {
+                    self.set_workspace_direction_strategy(
+                        monitor_idx,
+                        workspace_idx,
+                        direction_strategy,
+                    )?;
+                }
+            }
             SocketMessage::NamedWorkspaceLayout(ref workspace, layout) => {
                 if let Some((monitor_idx, workspace_idx)) =
                     self.monitor_workspace_index_by_name(workspace)
@@ -699,6 +782,7 @@ impl WindowManager {
                 tracing::info!(
                     "received stop command, restoring all hidden windows and terminating process"
                 );
+                Snapshot::persist(self);
                 self.restore_all_windows()?;
 
                 if WindowsApi::focus_follows_mouse()? {
@@ -783,7 +867,35 @@ impl WindowManager {
                     }
                 }
             }
+            SocketMessage::CheckLayout => {
+                let offset = self.work_area_offset;
+                let mut monitor_findings = HashMap::new();
+
+                for (index, monitor) in self.monitors().iter().enumerate() {
+                    monitor_findings.insert(
+                        monitor
+                            .device_id()
+                            .clone()
+                            .unwrap_or_else(|| format!("{index}")),
+                        monitor.check_focused_workspace_layout(offset)?,
+                    );
+                }
+
+                let findings = match serde_json::to_string_pretty(&monitor_findings) {
+                    Ok(findings) => findings,
+                    Err(error) => error.to_string(),
+                };
 
+                let socket = DATA_DIR.join("komorebic.sock");
+
+                let mut connected = false;
+                while !connected {
+                    if let Ok(mut stream) = UnixStream::connect(&socket) {
+                        connected = true;
+                        stream.write_all(findings.as_bytes())?;
+                    }
+                }
+            }
             SocketMessage::Query(query) => {
                 let response = match query {
                     StateQuery::FocusedMonitorIndex => self.focused_monitor_idx(),
@@ -814,44 +926,57 @@ impl WindowManager {
                 self.resize_window(direction, sizing, self.resize_delta, true)?;
             }
             SocketMessage::ResizeWindowAxis(axis, sizing) => {
-                // If the user has a custom layout, allow for the resizing of the primary column
-                // with this signal
+                // If the user has a custom layout, allow for the resizing of the column of the
+                // focused container with this signal
+                let resize_delta = self.resize_delta;
+                let area = self.focused_workspace_layout_area()?;
+                let work_area = self.focused_monitor_work_area()?;
+                let identifiers = self
+                    .focused_monitor()
+                    .ok_or_else(|| anyhow!("there is no monitor"))?
+                    .identifiers();
                 let workspace = self.focused_workspace_mut()?;
-                let container_len = workspace.containers().len();
+                let focused_idx = workspace.focused_container_idx();
                 let no_layout_rules = workspace.layout_rules().is_empty();
 
                 if let Layout::Custom(ref mut custom) = workspace.layout_mut() {
-                    if matches!(axis, Axis::Horizontal) {
-                        #[allow(clippy::cast_precision_loss)]
-                        let percentage = custom
-                            .primary_width_percentage()
-                            .unwrap_or(100.0 / (custom.len() as f32));
+                    // Columns are resized along the axis that they are laid out on
+                    let resizes_columns = match custom.orientation() {
+                        Orientation::Columns => matches!(axis, Axis::Horizontal),
+                        Orientation::Rows => matches!(axis, Axis::Vertical),
+                    };
+
+                    if resizes_columns {
+                        // The length of the area that the columns are laid out along
+                        let total = |custom: &CustomLayout| match custom.orientation() {
+                            Orientation::Columns => area.right,
+                            Orientation::Rows => area.bottom,
+                        };
 
                         if no_layout_rules {
-                            match sizing {
-                                Sizing::Increase => {
-                                    custom.set_primary_width_percentage(percentage + 5.0);
-                                }
-                                Sizing::Decrease => {
-                                    custom.set_primary_width_percentage(percentage - 5.0);
-                                }
-                            }
+                            let column_idx = custom.column_for_container_idx(focused_idx);
+                            let total = total(custom);
+                            custom.adjust_column_width(
+                                column_idx,
+                                sizing,
+                                5.0,
+                                resize_delta,
+                                total,
+                            );
                         } else {
-                            for rule in workspace.layout_rules_mut() {
-                                if container_len >= rule.0 {
-                                    if let Layout::Custom(ref mut custom) = rule.1 {
-                                        match sizing {
-                                            Sizing::Increase => {
-                                                custom
-                                                    .set_primary_width_percentage(percentage + 5.0);
-                                            }
-                                            Sizing::Decrease => {
-                                                custom
-                                                    .set_primary_width_percentage(percentage - 5.0);
-                                            }
-                                        }
-                                    }
-                                }
+                            // Only the rule which supplied the current layout is resized
+                            if let Some((_, Layout::Custom(custom))) =
+                                workspace.active_layout_rule_mut(&work_area, &identifiers)
+                            {
+                                let column_idx = custom.column_for_container_idx(focused_idx);
+                                let total = total(custom);
+                                custom.adjust_column_width(
+                                    column_idx,
+                                    sizing,
+                                    5.0,
+                                    resize_delta,
+                                    total,
+                                );
                             }
                         }
                     }
@@ -1134,6 +1259,7 @@ impl WindowManager {
                 serde_json::to_writer_pretty(&file, &resize)?;
             }
             SocketMessage::QuickLoad => {
+                let area = self.focused_workspace_layout_area()?;
                 let workspace = self.focused_workspace_mut()?;
 
                 let quicksave_json = std::env::temp_dir().join("komorebi.quicksave.json");
@@ -1141,9 +1267,9 @@ impl WindowManager {
                 let file = File::open(&quicksave_json)
                     .map_err(|_| anyhow!("no quicksave found at {}", quicksave_json.display()))?;
 
-                let resize: Vec<Option<Rect>> = serde_json::from_reader(file)?;
+                let resize: SavedResizeDimensions = serde_json::from_reader(file)?;
 
-                workspace.set_resize_dimensions(resize);
+                workspace.set_resize_dimensions(resize.into_ratios(&area));
                 self.update_focused_workspace(false)?;
             }
             SocketMessage::Save(ref path) => {
@@ -1159,16 +1285,29 @@ impl WindowManager {
                 serde_json::to_writer_pretty(&file, &resize)?;
             }
             SocketMessage::Load(ref path) => {
+                let area = self.focused_workspace_layout_area()?;
                 let workspace = self.focused_workspace_mut()?;
 
                 let file =
                     File::open(path).map_err(|_| anyhow!("no file found at {}", path.display()))?;
 
-                let resize: Vec<Option<Rect>> = serde_json::from_reader(file)?;
+                let resize: SavedResizeDimensions = serde_json::from_reader(file)?;
 
-                workspace.set_resize_dimensions(resize);
+                workspace.set_resize_dimensions(resize.into_ratios(&area));
                 self.update_focused_workspace(false)?;
             }
+            SocketMessage::SaveSnapshot(ref name) => {
+                Snapshot::from(&*self).save(name)?;
+            }
+            SocketMessage::LoadSnapshot(ref name) => {
+                Snapshot::load(name)?.restore(self)?;
+            }
+            SocketMessage::Undo => {
+                self.undo()?;
+            }
+            SocketMessage::Redo => {
+                self.redo()?;
+            }
             SocketMessage::AddSubscriber(ref subscriber) => {
                 let mut pipes = SUBSCRIPTION_PIPES.lock();
                 let pipe_path = format!(r"\\.\pipe\{subscriber}");
@@ -1348,6 +1487,18 @@ impl WindowManager {
             }
         };
 
+        // Commands which turned out to be no-ops, such as moving a window past the edge of a
+        // workspace, don't leave anything behind to be undone
+        if let Some(previous_arrangement) = previous_arrangement {
+            if !Snapshot::from(&previous_arrangement)
+                .same_arrangement(&Snapshot::from(&self.monitors))
+            {
+                self.history.record(previous_arrangement);
+            }
+        }
+
+        self.prune_marks();
+
         match message {
             SocketMessage::ToggleMonocle => {
                 let current = BORDER_COLOUR_CURRENT.load(Ordering::SeqCst);
@@ -1388,6 +1539,9 @@ impl WindowManager {
             | SocketMessage::CycleLayout(_)
             | SocketMessage::ChangeLayoutCustom(_)
             | SocketMessage::FlipLayout(_)
+            | SocketMessage::AdjustMainRatio(_, _)
+            | SocketMessage::AdjustMasterCount(_, _)
+            | SocketMessage::AdjustColumnRatio(_, _)
             | SocketMessage::ManageFocusedWindow
             | SocketMessage::MoveWorkspaceToMonitorNumber(_)
             | SocketMessage::MoveContainerToMonitorNumber(_)
@@ -1402,6 +1556,15 @@ impl WindowManager {
             | SocketMessage::StackWindow(_)
             | SocketMessage::UnstackWindow
             | SocketMessage::Retile
+            | SocketMessage::Balance(_)
+            | SocketMessage::LoadSnapshot(_)
+            | SocketMessage::Undo
+            | SocketMessage::Redo
+            | SocketMessage::SendToScratchpad(_)
+            | SocketMessage::ToggleScratchpad(_)
+            | SocketMessage::CycleScratchpad(_)
+            | SocketMessage::FocusMark(_)
+            | SocketMessage::SwapWithMark(_)
             // Adding this one so that changes can be seen instantly after
             // modifying the active window border offset
             | SocketMessage::ActiveWindowBorderOffset(_)
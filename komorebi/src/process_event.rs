@@ -31,6 +31,7 @@ use crate::BORDER_HWND;
 use crate::DATA_DIR;
 use crate::HIDDEN_HWNDS;
 use crate::REGEX_IDENTIFIERS;
+use crate::SIZE_CONSTRAINTS;
 use crate::TRAY_AND_MULTI_WINDOW_IDENTIFIERS;
 
 #[tracing::instrument]
@@ -75,6 +76,39 @@ impl WindowManager {
             }
         }
 
+        // Windows in the scratchpad are kept out of every workspace until they are closed, or
+        // until they are explicitly managed or unmanaged, which takes them out of the scratchpad
+        let window = event.window();
+        if self.scratchpad.contains(window.hwnd) {
+            match event {
+                WindowManagerEvent::Manage(_) => {
+                    self.scratchpad.remove(window.hwnd);
+                }
+                WindowManagerEvent::Destroy(_, _) | WindowManagerEvent::Unmanage(_) => {
+                    self.scratchpad.remove(window.hwnd);
+                    self.marks.remove_window(window.hwnd);
+                    return Ok(());
+                }
+                _ => return Ok(()),
+            }
+        } else if let WindowManagerEvent::Show(_, window) = event {
+            let is_managed = self.monitors().iter().any(|monitor| {
+                monitor
+                    .workspaces()
+                    .iter()
+                    .any(|workspace| workspace.contains_window(window.hwnd))
+            });
+
+            if !is_managed {
+                if let Some(name) = window.scratchpad_name()? {
+                    tracing::info!("sending window to the scratchpad: {name}");
+                    window.hide();
+                    self.scratchpad.add(name, *window);
+                    return Ok(());
+                }
+            }
+        }
+
         // Make sure we have the most recently focused monitor from any event
         match event {
             WindowManagerEvent::FocusChange(_, window)
@@ -114,6 +148,7 @@ impl WindowManager {
 
         for (i, monitor) in self.monitors_mut().iter_mut().enumerate() {
             let work_area = *monitor.work_area_size();
+            let identifiers = monitor.identifiers();
             let offset = if monitor.work_area_offset().is_some() {
                 monitor.work_area_offset()
             } else {
@@ -123,7 +158,7 @@ impl WindowManager {
             for (j, workspace) in monitor.workspaces_mut().iter_mut().enumerate() {
                 let reaped_orphans = workspace.reap_orphans()?;
                 if reaped_orphans.0 > 0 || reaped_orphans.1 > 0 {
-                    workspace.update(&work_area, offset, &invisible_borders)?;
+                    workspace.update(&work_area, offset, &invisible_borders, &identifiers)?;
                     tracing::info!(
                         "reaped {} orphan window(s) and {} orphaned container(s) on monitor: {}, workspace: {}",
                         reaped_orphans.0,
@@ -135,6 +170,7 @@ impl WindowManager {
             }
         }
 
+        self.prune_marks();
         self.enforce_workspace_rules()?;
 
         if matches!(event, WindowManagerEvent::MouseCapture(..)) {
@@ -150,6 +186,8 @@ impl WindowManager {
                 self.has_pending_raise_op = false;
             }
             WindowManagerEvent::Destroy(_, window) | WindowManagerEvent::Unmanage(window) => {
+                self.marks.remove_window(window.hwnd);
+                SIZE_CONSTRAINTS.lock().remove(&window.hwnd);
                 self.focused_workspace_mut()?.remove_window(window.hwnd)?;
                 self.update_focused_workspace(false)?;
 
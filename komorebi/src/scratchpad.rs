@@ -0,0 +1,117 @@
+use getset::CopyGetters;
+use getset::Getters;
+use getset::Setters;
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::window::Window;
+
+/// A window in the scratchpad, along with the name that it is toggled by
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct ScratchpadWindow {
+    pub name: String,
+    pub window: Window,
+}
+
+/// Windows which are kept hidden outside of every workspace until they are summoned, floating
+/// and centered, onto the focused monitor
+#[derive(Debug, Clone, Default, Serialize, Getters, CopyGetters, Setters, JsonSchema)]
+pub struct Scratchpad {
+    #[getset(get = "pub")]
+    windows: Vec<ScratchpadWindow>,
+    /// The index of the window which is currently summoned, if there is one
+    #[getset(get_copy = "pub", set = "pub")]
+    shown: Option<usize>,
+}
+
+impl Scratchpad {
+    pub fn contains(&self, hwnd: isize) -> bool {
+        self.windows.iter().any(|entry| entry.window.hwnd == hwnd)
+    }
+
+    /// The index of the first window with a name, as more than one window can share a name
+    pub fn idx_for_name(&self, name: &str) -> Option<usize> {
+        self.windows.iter().position(|entry| entry.name == name)
+    }
+
+    pub fn add(&mut self, name: String, window: Window) {
+        if !self.contains(window.hwnd) {
+            self.windows.push(ScratchpadWindow { name, window });
+        }
+    }
+
+    /// Takes a window out of the scratchpad, keeping track of which window is shown
+    pub fn remove(&mut self, hwnd: isize) -> Option<ScratchpadWindow> {
+        let idx = self
+            .windows
+            .iter()
+            .position(|entry| entry.window.hwnd == hwnd)?;
+
+        self.shown = match self.shown {
+            Some(shown) if shown == idx => None,
+            Some(shown) if shown > idx => Option::from(shown - 1),
+            shown => shown,
+        };
+
+        Option::from(self.windows.remove(idx))
+    }
+
+    /// Takes every window that matches out of the scratchpad, keeping track of which window is
+    /// shown
+    pub fn take_windows(&mut self, matches: impl Fn(isize) -> bool) -> Vec<Window> {
+        let hwnds = self
+            .windows
+            .iter()
+            .map(|entry| entry.window.hwnd)
+            .filter(|hwnd| matches(*hwnd))
+            .collect::<Vec<_>>();
+
+        hwnds
+            .into_iter()
+            .filter_map(|hwnd| self.remove(hwnd))
+            .map(|entry| entry.window)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::container::Container;
+    use crate::workspace::Workspace;
+
+    #[test]
+    fn windows_in_an_arrangement_are_taken_out() {
+        let mut workspace = Workspace::default();
+        for hwnd in [1, 2, 3] {
+            let mut container = Container::default();
+            container.add_window(Window { hwnd });
+            workspace.add_container(container);
+        }
+
+        let previous = workspace.clone();
+
+        let mut scratchpad = Scratchpad::default();
+        scratchpad.add(String::from("other"), Window { hwnd: 4 });
+        for hwnd in [2, 3] {
+            workspace
+                .remove_window(hwnd)
+                .expect("the window should be removed");
+            scratchpad.add(String::from("sent"), Window { hwnd });
+        }
+
+        scratchpad.set_shown(Option::from(2));
+
+        let taken = scratchpad.take_windows(|hwnd| previous.contains_window(hwnd));
+
+        assert_eq!(
+            taken.iter().map(|window| window.hwnd).collect::<Vec<_>>(),
+            vec![2, 3]
+        );
+        assert!(!scratchpad.contains(2));
+        assert!(!scratchpad.contains(3));
+        assert!(scratchpad.contains(4));
+        assert_eq!(scratchpad.shown(), None);
+    }
+}
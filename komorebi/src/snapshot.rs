@@ -0,0 +1,630 @@
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::path::Path;
+use std::path::PathBuf;
+
+use color_eyre::eyre::anyhow;
+use color_eyre::eyre::bail;
+use color_eyre::Result;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+
+use komorebi_core::Axis;
+use komorebi_core::Layout;
+use komorebi_core::LayoutOptions;
+use komorebi_core::Padding;
+use komorebi_core::ResizeRatio;
+
+use crate::container::Container;
+use crate::monitor::Monitor;
+use crate::ring::Ring;
+use crate::window::Window;
+use crate::window_manager::WindowManager;
+use crate::windows_api::WindowsApi;
+use crate::workspace::Workspace;
+use crate::DATA_DIR;
+
+/// Identifies a window by the handle, executable, class and title that it had when a snapshot
+/// was saved
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct WindowMatcher {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hwnd: Option<isize>,
+    pub exe: String,
+    pub class: String,
+    pub title: String,
+}
+
+impl TryFrom<Window> for WindowMatcher {
+    type Error = color_eyre::Report;
+
+    fn try_from(window: Window) -> Result<Self> {
+        Ok(Self {
+            hwnd: Option::from(window.hwnd),
+            exe: window.exe()?,
+            class: window.class()?,
+            title: window.title()?,
+        })
+    }
+}
+
+/// How closely an open window has to match a saved window to take its place
+#[derive(Clone, Copy)]
+enum Precision {
+    /// The same window, which is still open under the same handle
+    Handle,
+    /// A window with the same title
+    Title,
+    /// Any window with the same executable and class
+    Class,
+}
+
+impl WindowMatcher {
+    /// Windows with the same executable and class can always take each other's place, so a
+    /// handle which has been reused by another application is never mistaken for a match
+    fn matches(&self, other: &Self, precision: Precision) -> bool {
+        self.exe == other.exe
+            && self.class == other.class
+            && match precision {
+                Precision::Handle => self.hwnd.is_some() && self.hwnd == other.hwnd,
+                Precision::Title => self.title == other.title,
+                Precision::Class => true,
+            }
+    }
+}
+
+/// The windows stacked in a container, in order
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ContainerSnapshot {
+    pub windows: Vec<WindowMatcher>,
+    pub focused_window: usize,
+}
+
+impl From<&Container> for ContainerSnapshot {
+    fn from(container: &Container) -> Self {
+        let mut windows = vec![];
+        let mut focused_window = 0;
+
+        // Windows which have been closed without being reaped yet are left out
+        for (idx, window) in container.windows().iter().enumerate() {
+            if let Ok(matcher) = WindowMatcher::try_from(*window) {
+                if idx == container.focused_window_idx() {
+                    focused_window = windows.len();
+                }
+
+                windows.push(matcher);
+            }
+        }
+
+        Self {
+            windows,
+            focused_window,
+        }
+    }
+}
+
+impl ContainerSnapshot {
+    /// Takes the next window for each of the saved windows, and returns a container if any of
+    /// them were matched
+    fn restore(&self, windows: &mut impl Iterator<Item = Option<Window>>) -> Option<Container> {
+        let mut container = Container::default();
+        let mut focused_window = 0;
+
+        for idx in 0..self.windows.len() {
+            if let Some(window) = windows.next().flatten() {
+                if idx == self.focused_window {
+                    focused_window = container.windows().len();
+                }
+
+                container.windows_mut().push_back(window);
+            }
+        }
+
+        if container.windows().is_empty() {
+            None
+        } else {
+            container.focus_window(focused_window);
+            Option::from(container)
+        }
+    }
+}
+
+/// The layout of a workspace and the windows in each of its containers
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct WorkspaceSnapshot {
+    pub layout: Layout,
+    pub layout_flip: Option<Axis>,
+    pub layout_options: LayoutOptions,
+    pub workspace_padding: Option<Padding>,
+    pub container_padding: Option<Padding>,
+    pub resize_dimensions: Vec<Option<ResizeRatio>>,
+    pub containers: Vec<ContainerSnapshot>,
+    pub focused_container: usize,
+    pub floating_windows: Vec<WindowMatcher>,
+    pub monocle_container: Option<ContainerSnapshot>,
+    pub monocle_container_restore_idx: Option<usize>,
+    pub maximized_window: Option<WindowMatcher>,
+    pub maximized_window_restore_idx: Option<usize>,
+}
+
+impl From<&Workspace> for WorkspaceSnapshot {
+    fn from(workspace: &Workspace) -> Self {
+        let mut containers = vec![];
+        let mut resize_dimensions = vec![];
+        let mut focused_container = 0;
+
+        for (idx, container) in workspace.containers().iter().enumerate() {
+            let snapshot = ContainerSnapshot::from(container);
+            if snapshot.windows.is_empty() {
+                continue;
+            }
+
+            if idx == workspace.focused_container_idx() {
+                focused_container = containers.len();
+            }
+
+            containers.push(snapshot);
+            resize_dimensions.push(workspace.resize_dimensions().get(idx).copied().flatten());
+        }
+
+        Self {
+            layout: workspace.layout().clone(),
+            layout_flip: workspace.layout_flip(),
+            layout_options: workspace.layout_options(),
+            workspace_padding: workspace.workspace_padding(),
+            container_padding: workspace.container_padding(),
+            resize_dimensions,
+            containers,
+            focused_container,
+            floating_windows: workspace
+                .floating_windows()
+                .iter()
+                .filter_map(|window| WindowMatcher::try_from(*window).ok())
+                .collect(),
+            monocle_container: workspace
+                .monocle_container()
+                .as_ref()
+                .map(ContainerSnapshot::from),
+            monocle_container_restore_idx: workspace.monocle_container_restore_idx(),
+            maximized_window: workspace
+                .maximized_window()
+                .as_ref()
+                .and_then(|window| WindowMatcher::try_from(*window).ok()),
+            maximized_window_restore_idx: workspace.maximized_window_restore_idx(),
+        }
+    }
+}
+
+impl WorkspaceSnapshot {
+    /// Every saved window, in the order that [`WorkspaceSnapshot::restore`] takes them
+    fn slots(&self) -> Vec<&WindowMatcher> {
+        let mut slots = vec![];
+
+        for container in &self.containers {
+            slots.extend(&container.windows);
+        }
+
+        if let Some(container) = &self.monocle_container {
+            slots.extend(&container.windows);
+        }
+
+        slots.extend(&self.maximized_window);
+        slots.extend(&self.floating_windows);
+
+        slots
+    }
+
+    /// Rebuilds a workspace which has had all of its windows taken out, using the next window
+    /// for each of the slots of the snapshot
+    fn restore(
+        &self,
+        workspace: &mut Workspace,
+        windows: &mut impl Iterator<Item = Option<Window>>,
+    ) {
+        workspace.set_layout(self.layout.clone());
+        workspace.set_layout_flip(self.layout_flip);
+        workspace.set_layout_options(self.layout_options);
+        workspace.set_workspace_padding(self.workspace_padding);
+        workspace.set_container_padding(self.container_padding);
+
+        let mut resize_dimensions = vec![];
+        let mut focused_container = 0;
+
+        // Containers without any matching windows are dropped along with their resize adjustments
+        for (idx, snapshot) in self.containers.iter().enumerate() {
+            if let Some(container) = snapshot.restore(windows) {
+                if idx == self.focused_container {
+                    focused_container = workspace.containers().len();
+                }
+
+                workspace.containers_mut().push_back(container);
+                resize_dimensions.push(self.resize_dimensions.get(idx).copied().flatten());
+            }
+        }
+
+        workspace.set_resize_dimensions(resize_dimensions);
+        workspace.focus_container(focused_container);
+
+        let len = workspace.containers().len();
+
+        if let Some(snapshot) = &self.monocle_container {
+            if let Some(container) = snapshot.restore(windows) {
+                workspace.set_monocle_container(Option::from(container));
+                workspace.set_monocle_container_restore_idx(Option::from(
+                    self.monocle_container_restore_idx.unwrap_or(len).min(len),
+                ));
+            }
+        }
+
+        if self.maximized_window.is_some() {
+            if let Some(window) = windows.next().flatten() {
+                workspace.set_maximized_window(Option::from(window));
+                workspace.set_maximized_window_restore_idx(Option::from(
+                    self.maximized_window_restore_idx.unwrap_or(len).min(len),
+                ));
+            }
+        }
+
+        for _ in &self.floating_windows {
+            if let Some(window) = windows.next().flatten() {
+                workspace.floating_windows_mut().push(window);
+            }
+        }
+    }
+}
+
+/// The workspaces of a monitor, which are restored to the monitor with the same device id
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct MonitorSnapshot {
+    pub device_id: Option<String>,
+    pub workspaces: Vec<WorkspaceSnapshot>,
+    pub focused_workspace: usize,
+}
+
+/// The arrangement of the windows on every workspace of every monitor
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Snapshot {
+    pub monitors: Vec<MonitorSnapshot>,
+}
+
+/// A managed window which was taken out of its workspace while a snapshot is being restored
+struct OpenWindow {
+    window: Window,
+    matcher: Option<WindowMatcher>,
+    monitor_idx: usize,
+    workspace_idx: usize,
+    floating: bool,
+}
+
+impl From<&WindowManager> for Snapshot {
+    fn from(wm: &WindowManager) -> Self {
+        Self::from(&wm.monitors)
+    }
+}
+
+impl From<&Ring<Monitor>> for Snapshot {
+    fn from(monitors: &Ring<Monitor>) -> Self {
+        Self {
+            monitors: monitors
+                .elements()
+                .iter()
+                .map(|monitor| MonitorSnapshot {
+                    device_id: monitor.device_id().clone(),
+                    workspaces: monitor
+                        .workspaces()
+                        .iter()
+                        .map(WorkspaceSnapshot::from)
+                        .collect(),
+                    focused_workspace: monitor.focused_workspace_idx(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl Snapshot {
+    /// Whether two snapshots arrange the same windows in the same way, compared through their
+    /// serialized form as layouts cannot be compared directly
+    pub fn same_arrangement(&self, other: &Self) -> bool {
+        match (serde_json::to_value(self), serde_json::to_value(other)) {
+            (Ok(this), Ok(other)) => this == other,
+            _ => false,
+        }
+    }
+
+    /// The file that a named snapshot is stored in
+    pub fn path(name: &str) -> Result<PathBuf> {
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+        {
+            bail!("snapshot names can only contain letters, numbers, dashes and underscores");
+        }
+
+        Ok(DATA_DIR.join("snapshots").join(format!("{name}.json")))
+    }
+
+    /// The file that the state of the window manager is persisted to, so that it can be restored
+    /// when komorebi is started again
+    pub fn state_path() -> PathBuf {
+        DATA_DIR.join("komorebi.state.json")
+    }
+
+    /// Writes the state of the window manager to [`Snapshot::state_path`], logging any failure
+    /// instead of returning it so that it never stops komorebi from shutting down
+    pub fn persist(wm: &WindowManager) {
+        if let Err(error) = Self::from(wm).save_to(&Self::state_path()) {
+            tracing::warn!("could not persist the window manager state: {error}");
+        }
+    }
+
+    pub fn save(&self, name: &str) -> Result<()> {
+        self.save_to(&Self::path(name)?)
+    }
+
+    /// Writes the snapshot to a temporary file next to the path before moving it into place, so
+    /// that a snapshot which is interrupted while it is being written never replaces the last one
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let temporary = path.with_extension("json.tmp");
+        let file = OpenOptions::new()
+            .write(true)
+            .truncate(true)
+            .create(true)
+            .open(&temporary)?;
+
+        serde_json::to_writer_pretty(&file, self)?;
+        file.sync_all()?;
+
+        std::fs::rename(&temporary, path)?;
+
+        Ok(())
+    }
+
+    pub fn load(name: &str) -> Result<Self> {
+        let path = Self::path(name)?;
+        if !path.is_file() {
+            bail!("no snapshot named {name} found at {}", path.display());
+        }
+
+        Self::load_from(&path)
+    }
+
+    pub fn load_from(path: &Path) -> Result<Self> {
+        let file =
+            File::open(path).map_err(|_| anyhow!("no snapshot found at {}", path.display()))?;
+
+        Ok(serde_json::from_reader(file)?)
+    }
+
+    /// Shows the saved windows which are still open but aren't managed, such as windows that were
+    /// hidden on another workspace when komorebi last stopped without restoring them, and adds
+    /// them to the focused workspace so that they can be matched by their handles
+    pub fn adopt_hidden_windows(&self, wm: &mut WindowManager) -> Result<()> {
+        let saved = self
+            .monitors
+            .iter()
+            .flat_map(|monitor| &monitor.workspaces)
+            .flat_map(WorkspaceSnapshot::slots)
+            .collect::<Vec<_>>();
+
+        for matcher in saved {
+            if let Some(hwnd) = matcher.hwnd {
+                let window = Window { hwnd };
+                let managed = wm.monitors().iter().any(|monitor| {
+                    monitor
+                        .workspaces()
+                        .iter()
+                        .any(|workspace| workspace.contains_window(hwnd))
+                });
+
+                if managed || !window.is_window() {
+                    continue;
+                }
+
+                let is_same_window = WindowMatcher::try_from(window)
+                    .is_ok_and(|current| current.matches(matcher, Precision::Class));
+
+                let is_hidden = !WindowsApi::is_window_visible(window.hwnd())
+                    || WindowsApi::is_iconic(window.hwnd())
+                    || window.is_cloaked().unwrap_or_default();
+
+                if is_same_window && is_hidden {
+                    window.restore();
+
+                    let mut container = Container::default();
+                    container.windows_mut().push_back(window);
+                    wm.focused_workspace_mut()?.add_container(container);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The index of the monitor that each saved monitor is restored to, preferring a monitor with
+    /// the same device id and falling back to the monitor at the same index
+    fn monitor_targets(&self, wm: &WindowManager) -> Vec<Option<usize>> {
+        let mut targets: Vec<Option<usize>> = vec![];
+
+        for (idx, snapshot) in self.monitors.iter().enumerate() {
+            let by_device_id = snapshot.device_id.as_ref().and_then(|device_id| {
+                wm.monitors()
+                    .iter()
+                    .position(|monitor| monitor.device_id().as_ref() == Some(device_id))
+            });
+
+            let target = by_device_id
+                .or_else(|| (idx < wm.monitors().len()).then_some(idx))
+                .filter(|target| !targets.contains(&Some(*target)));
+
+            targets.push(target);
+        }
+
+        targets
+    }
+
+    /// Moves the windows that are currently managed on the workspaces of the snapshot into its
+    /// slots, where windows which don't match any slot are put back on the workspace that they
+    /// were taken from
+    pub fn restore(&self, wm: &mut WindowManager) -> Result<()> {
+        let targets = self.monitor_targets(wm);
+        let mut open = vec![];
+
+        // Workspaces which the snapshot doesn't have a slot for are left exactly as they are
+        let covered = |monitor_idx: usize, workspace_idx: usize| {
+            self.monitors
+                .iter()
+                .zip(&targets)
+                .any(|(snapshot, target)| {
+                    *target == Some(monitor_idx) && workspace_idx < snapshot.workspaces.len()
+                })
+        };
+
+        for (monitor_idx, monitor) in wm.monitors_mut().iter_mut().enumerate() {
+            for (workspace_idx, workspace) in monitor.workspaces_mut().iter_mut().enumerate() {
+                if !covered(monitor_idx, workspace_idx) {
+                    continue;
+                }
+
+                let mut tiled = vec![];
+
+                for container in workspace.containers_mut().drain(..) {
+                    tiled.extend(container.windows().iter().copied());
+                }
+
+                if let Some(container) = workspace.monocle_container_mut().take() {
+                    tiled.extend(container.windows().iter().copied());
+                }
+
+                tiled.extend(workspace.maximized_window_mut().take());
+
+                workspace.focus_container(0);
+                workspace.set_monocle_container_restore_idx(None);
+                workspace.set_maximized_window_restore_idx(None);
+                workspace.resize_dimensions_mut().clear();
+
+                let floating = workspace.floating_windows_mut().drain(..);
+
+                for (window, floating) in tiled
+                    .into_iter()
+                    .map(|window| (window, false))
+                    .chain(floating.map(|window| (window, true)))
+                {
+                    open.push(OpenWindow {
+                        window,
+                        matcher: WindowMatcher::try_from(window).ok(),
+                        monitor_idx,
+                        workspace_idx,
+                        floating,
+                    });
+                }
+            }
+        }
+
+        let mut slots = vec![];
+        for (snapshot, target) in self.monitors.iter().zip(&targets) {
+            if target.is_some() {
+                for workspace in &snapshot.workspaces {
+                    slots.extend(workspace.slots());
+                }
+            }
+        }
+
+        let assigned = assign(&slots, &open);
+        let mut windows = assigned.iter().map(|idx| idx.map(|idx| open[idx].window));
+
+        for (snapshot, target) in self.monitors.iter().zip(&targets) {
+            if let Some(target) = target {
+                let monitor = wm
+                    .monitors_mut()
+                    .get_mut(*target)
+                    .ok_or_else(|| anyhow!("there is no monitor"))?;
+
+                monitor.ensure_workspace_count(snapshot.workspaces.len());
+
+                for (workspace_idx, workspace) in snapshot.workspaces.iter().enumerate() {
+                    if let Some(current) = monitor.workspaces_mut().get_mut(workspace_idx) {
+                        workspace.restore(current, &mut windows);
+                    }
+                }
+
+                monitor.focus_workspace(snapshot.focused_workspace)?;
+            }
+        }
+
+        for (idx, window) in open.iter().enumerate() {
+            if assigned.contains(&Some(idx)) {
+                continue;
+            }
+
+            if let Some(workspace) = wm
+                .monitors_mut()
+                .get_mut(window.monitor_idx)
+                .and_then(|monitor| monitor.workspaces_mut().get_mut(window.workspace_idx))
+            {
+                if window.floating {
+                    workspace.floating_windows_mut().push(window.window);
+                } else {
+                    let mut container = Container::default();
+                    container.windows_mut().push_back(window.window);
+                    workspace.containers_mut().push_back(container);
+                    workspace.resize_dimensions_mut().push(None);
+                }
+            }
+        }
+
+        // Windows which have moved to another workspace or further down a stack are hidden, and
+        // windows which have moved to the front are shown again
+        for monitor in wm.monitors_mut() {
+            if let Some(workspace) = monitor.focused_workspace_mut() {
+                for container in workspace.containers_mut() {
+                    container.load_focused_window();
+                }
+            }
+
+            monitor.load_focused_workspace(false)?;
+        }
+
+        wm.retile_all(true)?;
+
+        let mouse_follows_focus = wm.mouse_follows_focus;
+        wm.update_focused_workspace(mouse_follows_focus)
+    }
+}
+
+/// Assigns the open windows to the slots of a snapshot, where every slot is first given the same
+/// window if it is still open, then a window with the same title, and then any window with the
+/// same executable and class
+fn assign(slots: &[&WindowMatcher], open: &[OpenWindow]) -> Vec<Option<usize>> {
+    let mut assigned = vec![None; slots.len()];
+    let mut taken = vec![false; open.len()];
+
+    for precision in [Precision::Handle, Precision::Title, Precision::Class] {
+        for (slot, matcher) in slots.iter().enumerate() {
+            if assigned[slot].is_some() {
+                continue;
+            }
+
+            let candidate = open.iter().zip(&taken).position(|(window, taken)| {
+                !taken
+                    && window
+                        .matcher
+                        .as_ref()
+                        .is_some_and(|candidate| candidate.matches(matcher, precision))
+            });
+
+            if let Some(idx) = candidate {
+                taken[idx] = true;
+                assigned[slot] = Option::from(idx);
+            }
+        }
+    }
+
+    assigned
+}
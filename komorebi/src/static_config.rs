@@ -1,7 +1,10 @@
 use crate::border::Border;
 use crate::current_virtual_desktop;
+use crate::history::History;
+use crate::marks::Marks;
 use crate::monitor::Monitor;
 use crate::ring::Ring;
+use crate::scratchpad::Scratchpad;
 use crate::window_manager::WindowManager;
 use crate::window_manager_event::WindowManagerEvent;
 use crate::windows_api::WindowsApi;
@@ -27,6 +30,9 @@ use crate::MANAGE_IDENTIFIERS;
 use crate::MONITOR_INDEX_PREFERENCES;
 use crate::OBJECT_NAME_CHANGE_ON_LAUNCH;
 use crate::REGEX_IDENTIFIERS;
+use crate::SCRATCHPAD_IDENTIFIERS;
+use crate::SIZE_CONSTRAINTS;
+use crate::SIZE_CONSTRAINT_RULES;
 use crate::TRAY_AND_MULTI_WINDOW_IDENTIFIERS;
 use crate::WORKSPACE_RULES;
 use color_eyre::Result;
@@ -39,19 +45,33 @@ use komorebi_core::config_generation::IdWithIdentifier;
 use komorebi_core::config_generation::MatchingStrategy;
 use komorebi_core::resolve_home_path;
 use komorebi_core::ApplicationIdentifier;
+use komorebi_core::BspStrategy;
+use komorebi_core::ContainerConstraints;
 use komorebi_core::DefaultLayout;
+use komorebi_core::DirectionStrategy;
 use komorebi_core::FocusFollowsMouseImplementation;
 use komorebi_core::HidingBehaviour;
 use komorebi_core::Layout;
+use komorebi_core::LayoutCondition;
+use komorebi_core::LayoutOptions;
 use komorebi_core::MoveBehaviour;
 use komorebi_core::OperationBehaviour;
+use komorebi_core::Padding;
 use komorebi_core::Rect;
+use komorebi_core::SizeLimit;
 use komorebi_core::SocketMessage;
+use komorebi_core::StackPlacement;
 use komorebi_core::WindowContainerBehaviour;
 use parking_lot::Mutex;
 use regex::Regex;
 use schemars::JsonSchema;
+use serde::de::value::MapAccessDeserializer;
+use serde::de::value::SeqAccessDeserializer;
+use serde::de::MapAccess;
+use serde::de::SeqAccess;
+use serde::de::Visitor;
 use serde::Deserialize;
+use serde::Deserializer;
 use serde::Serialize;
 use std::collections::HashMap;
 use std::collections::HashSet;
@@ -93,6 +113,67 @@ pub struct ActiveWindowBorderColours {
     pub monocle: Rgb,
 }
 
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(untagged)]
+pub enum LayoutRules {
+    /// Layouts keyed by the minimum number of containers on the workspace
+    ContainerCount(HashMap<usize, DefaultLayout>),
+    /// Layouts which apply when all of the conditions of a rule are met, where later rules with
+    /// the same minimum number of containers take precedence
+    Conditional(Vec<LayoutRuleConfig>),
+}
+
+// An untagged enum can't be deserialized here, as the buffering it relies on turns the integer keys
+// of the original format into strings before they are deserialized
+impl<'de> Deserialize<'de> for LayoutRules {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct LayoutRulesVisitor;
+
+        impl<'de> Visitor<'de> for LayoutRulesVisitor {
+            type Value = LayoutRules;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter
+                    .write_str("a map of container counts to layouts or a list of layout rules")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+                Deserialize::deserialize(MapAccessDeserializer::new(map))
+                    .map(LayoutRules::ContainerCount)
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<Self::Value, A::Error> {
+                Deserialize::deserialize(SeqAccessDeserializer::new(seq))
+                    .map(LayoutRules::Conditional)
+            }
+        }
+
+        deserializer.deserialize_any(LayoutRulesVisitor)
+    }
+}
+
+/// A layout to use when all of the given conditions are met
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct LayoutRuleConfig {
+    #[serde(flatten)]
+    pub condition: LayoutCondition,
+    /// Layout to use when the conditions are met
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub layout: Option<DefaultLayout>,
+    /// Custom layout to use when the conditions are met
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub custom_layout: Option<PathBuf>,
+}
+
+/// Size constraints for the windows of an application, which are honoured when they are tiled
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct SizeConstraintRule {
+    #[serde(flatten)]
+    pub identifier: IdWithIdentifier,
+    #[serde(flatten)]
+    pub constraints: ContainerConstraints,
+}
+
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct WorkspaceConfig {
     /// Name
@@ -105,16 +186,49 @@ pub struct WorkspaceConfig {
     pub custom_layout: Option<PathBuf>,
     /// Layout rules (default: None)
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub layout_rules: Option<HashMap<usize, DefaultLayout>>,
+    pub layout_rules: Option<LayoutRules>,
     /// Layout rules (default: None)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub custom_layout_rules: Option<HashMap<usize, PathBuf>>,
     /// Container padding (default: global)
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub container_padding: Option<i32>,
+    pub container_padding: Option<Padding>,
     /// Container padding (default: global)
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub workspace_padding: Option<i32>,
+    pub workspace_padding: Option<Padding>,
+    /// Remove the container and workspace padding when only one container is visible (default: false)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub smart_gaps: Option<bool>,
+    /// Maximum width of each container, in pixels or as a percentage of the work area, with
+    /// narrower containers centered where they would have been laid out (default: None)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_width: Option<SizeLimit>,
+    /// Maximum height of each container, in pixels or as a percentage of the work area, with
+    /// shorter containers centered where they would have been laid out (default: None)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_height: Option<SizeLimit>,
+    /// Ratio of the work area taken up by the main area of stack layouts (default: 0.5)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub main_ratio: Option<f32>,
+    /// Number of containers in the main area of stack layouts (default: 1)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub master_count: Option<usize>,
+    /// Ratio of the work area taken up by each column of the Scrolling layout (default: 0.5)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column_ratio: Option<f32>,
+    /// Strategy used to split containers in the BSP layout (default: Dwindle)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bsp_strategy: Option<BspStrategy>,
+    /// How stack containers are placed on either side of the CenteredMaster layout (default: Alternate)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stack_placement: Option<StackPlacement>,
+    /// Width in pixels of a lone container in the CenteredMaster layout, which is kept in the
+    /// middle of the work area (default: None)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub single_container_width: Option<i32>,
+    /// Strategy used to find the container in a given direction (default: Layout)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub direction_strategy: Option<DirectionStrategy>,
     /// Initial workspace application rules
     #[serde(skip_serializing_if = "Option::is_none")]
     pub initial_workspace_rules: Option<Vec<IdWithIdentifier>>,
@@ -125,16 +239,40 @@ pub struct WorkspaceConfig {
 
 impl From<&Workspace> for WorkspaceConfig {
     fn from(value: &Workspace) -> Self {
-        let mut layout_rules = HashMap::new();
-        for (threshold, layout) in value.layout_rules() {
+        let mut rules = vec![];
+        for (condition, layout) in value.layout_rules() {
             match layout {
                 Layout::Default(value) => {
-                    layout_rules.insert(*threshold, *value);
+                    rules.push((condition.clone(), *value));
                 }
-                Layout::Custom(_) => {}
+                Layout::Custom(_) | Layout::Tree(_) => {}
             }
         }
 
+        // Rules which only depend on the number of containers keep the original format
+        let layout_rules = if rules
+            .iter()
+            .all(|(condition, _)| condition.container_count_only().is_some())
+        {
+            LayoutRules::ContainerCount(
+                rules
+                    .into_iter()
+                    .map(|(condition, layout)| (condition.containers, layout))
+                    .collect(),
+            )
+        } else {
+            LayoutRules::Conditional(
+                rules
+                    .into_iter()
+                    .map(|(condition, layout)| LayoutRuleConfig {
+                        condition,
+                        layout: Option::from(layout),
+                        custom_layout: None,
+                    })
+                    .collect(),
+            )
+        };
+
         let workspace_rules = WORKSPACE_RULES.lock();
         let mut initial_ws_rules = vec![];
         let mut ws_rules = vec![];
@@ -166,8 +304,8 @@ impl From<&Workspace> for WorkspaceConfig {
             Option::from(ws_rules)
         };
 
-        let default_container_padding = DEFAULT_CONTAINER_PADDING.load(Ordering::SeqCst);
-        let default_workspace_padding = DEFAULT_WORKSPACE_PADDING.load(Ordering::SeqCst);
+        let default_container_padding = *DEFAULT_CONTAINER_PADDING.lock();
+        let default_workspace_padding = *DEFAULT_WORKSPACE_PADDING.lock();
 
         let container_padding = value.container_padding().and_then(|container_padding| {
             if container_padding == default_container_padding {
@@ -185,6 +323,44 @@ impl From<&Workspace> for WorkspaceConfig {
             }
         });
 
+        let default_layout_options = LayoutOptions::default();
+        let layout_options = value.layout_options();
+
+        #[allow(clippy::float_cmp)]
+        let main_ratio = if layout_options.main_ratio == default_layout_options.main_ratio {
+            None
+        } else {
+            Option::from(layout_options.main_ratio)
+        };
+
+        let master_count = if layout_options.master_count == default_layout_options.master_count {
+            None
+        } else {
+            Option::from(layout_options.master_count)
+        };
+
+        #[allow(clippy::float_cmp)]
+        let column_ratio = if layout_options.column_ratio == default_layout_options.column_ratio {
+            None
+        } else {
+            Option::from(layout_options.column_ratio)
+        };
+
+        let bsp_strategy = match layout_options.bsp_strategy {
+            BspStrategy::Dwindle => None,
+            bsp_strategy => Option::from(bsp_strategy),
+        };
+
+        let stack_placement = match layout_options.stack_placement {
+            StackPlacement::Alternate => None,
+            stack_placement => Option::from(stack_placement),
+        };
+
+        let direction_strategy = match value.direction_strategy() {
+            DirectionStrategy::Layout => None,
+            direction_strategy => Option::from(direction_strategy),
+        };
+
         Self {
             name: value
                 .name()
@@ -193,7 +369,7 @@ impl From<&Workspace> for WorkspaceConfig {
             layout: match value.layout() {
                 Layout::Default(layout) => Option::from(*layout),
                 // TODO: figure out how we might resolve file references in the future
-                Layout::Custom(_) => None,
+                Layout::Custom(_) | Layout::Tree(_) => None,
             },
             custom_layout: None,
             layout_rules: Option::from(layout_rules),
@@ -201,6 +377,16 @@ impl From<&Workspace> for WorkspaceConfig {
             custom_layout_rules: None,
             container_padding,
             workspace_padding,
+            smart_gaps: value.smart_gaps().then_some(true),
+            max_width: value.max_width(),
+            max_height: value.max_height(),
+            main_ratio,
+            master_count,
+            column_ratio,
+            bsp_strategy,
+            stack_placement,
+            single_container_width: layout_options.single_container_width,
+            direction_strategy,
             initial_workspace_rules: initial_ws_rules,
             workspace_rules: ws_rules,
         }
@@ -276,10 +462,10 @@ pub struct StaticConfig {
     pub active_window_border_colours: Option<ActiveWindowBorderColours>,
     /// Global default workspace padding (default: 10)
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub default_workspace_padding: Option<i32>,
+    pub default_workspace_padding: Option<Padding>,
     /// Global default container padding (default: 10)
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub default_container_padding: Option<i32>,
+    pub default_container_padding: Option<Padding>,
     /// Monitor and workspace configurations
     #[serde(skip_serializing_if = "Option::is_none")]
     pub monitors: Option<Vec<MonitorConfig>>,
@@ -298,6 +484,12 @@ pub struct StaticConfig {
     /// Individual window force-manage rules
     #[serde(skip_serializing_if = "Option::is_none")]
     pub manage_rules: Option<Vec<IdWithIdentifier>>,
+    /// Individual window scratchpad rules, where matching windows are named after the rule id
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scratchpad_rules: Option<Vec<IdWithIdentifier>>,
+    /// Minimum size, aspect ratio and fixed width constraints for individual windows
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size_constraint_rules: Option<Vec<SizeConstraintRule>>,
     /// Identify border overflow applications
     #[serde(skip_serializing_if = "Option::is_none")]
     pub border_overflow_applications: Option<Vec<IdWithIdentifier>>,
@@ -419,18 +611,16 @@ impl From<&WindowManager> for StaticConfig {
             border_offset: None,
             active_window_border: Option::from(BORDER_ENABLED.load(Ordering::SeqCst)),
             active_window_border_colours: border_colours,
-            default_workspace_padding: Option::from(
-                DEFAULT_WORKSPACE_PADDING.load(Ordering::SeqCst),
-            ),
-            default_container_padding: Option::from(
-                DEFAULT_CONTAINER_PADDING.load(Ordering::SeqCst),
-            ),
+            default_workspace_padding: Option::from(*DEFAULT_WORKSPACE_PADDING.lock()),
+            default_container_padding: Option::from(*DEFAULT_CONTAINER_PADDING.lock()),
             monitors: Option::from(monitors),
             alt_focus_hack: Option::from(ALT_FOCUS_HACK.load(Ordering::SeqCst)),
             window_hiding_behaviour: Option::from(*HIDING_BEHAVIOUR.lock()),
             global_work_area_offset: value.work_area_offset,
             float_rules: None,
             manage_rules: None,
+            scratchpad_rules: None,
+            size_constraint_rules: None,
             border_overflow_applications: None,
             tray_and_multi_window_applications: None,
             layered_applications: None,
@@ -464,11 +654,11 @@ impl StaticConfig {
         }
 
         if let Some(container) = self.default_container_padding {
-            DEFAULT_CONTAINER_PADDING.store(container, Ordering::SeqCst);
+            *DEFAULT_CONTAINER_PADDING.lock() = container;
         }
 
         if let Some(workspace) = self.default_workspace_padding {
-            DEFAULT_WORKSPACE_PADDING.store(workspace, Ordering::SeqCst);
+            *DEFAULT_WORKSPACE_PADDING.lock() = workspace;
         }
 
         self.active_window_border_width.map_or_else(
@@ -519,6 +709,7 @@ impl StaticConfig {
         let mut float_identifiers = FLOAT_IDENTIFIERS.lock();
         let mut regex_identifiers = REGEX_IDENTIFIERS.lock();
         let mut manage_identifiers = MANAGE_IDENTIFIERS.lock();
+        let mut scratchpad_identifiers = SCRATCHPAD_IDENTIFIERS.lock();
         let mut tray_and_multi_window_identifiers = TRAY_AND_MULTI_WINDOW_IDENTIFIERS.lock();
         let mut border_overflow_identifiers = BORDER_OVERFLOW_IDENTIFIERS.lock();
         let mut object_name_change_identifiers = OBJECT_NAME_CHANGE_ON_LAUNCH.lock();
@@ -558,6 +749,55 @@ impl StaticConfig {
             }
         }
 
+        if let Some(scratchpad) = &mut self.scratchpad_rules {
+            for identifier in scratchpad {
+                if identifier.matching_strategy.is_none() {
+                    identifier.matching_strategy = Option::from(MatchingStrategy::Legacy);
+                }
+
+                if !scratchpad_identifiers.contains(identifier) {
+                    scratchpad_identifiers.push(identifier.clone());
+
+                    if matches!(identifier.matching_strategy, Some(MatchingStrategy::Regex)) {
+                        let re = Regex::new(&identifier.id)?;
+                        regex_identifiers.insert(identifier.id.clone(), re);
+                    }
+                }
+            }
+        }
+
+        if let Some(rules) = &mut self.size_constraint_rules {
+            let mut size_constraint_rules = SIZE_CONSTRAINT_RULES.lock();
+
+            // Managed windows look up their constraints again against the reloaded rules
+            SIZE_CONSTRAINTS.lock().clear();
+
+            for rule in rules {
+                if rule.identifier.matching_strategy.is_none() {
+                    rule.identifier.matching_strategy = Option::from(MatchingStrategy::Legacy);
+                }
+
+                // Reloading the configuration updates the constraints of identifiers which are
+                // already known, rather than adding a rule that would never be reached
+                if let Some(existing) = size_constraint_rules
+                    .iter_mut()
+                    .find(|(identifier, _)| *identifier == rule.identifier)
+                {
+                    existing.1 = rule.constraints;
+                } else {
+                    size_constraint_rules.push((rule.identifier.clone(), rule.constraints));
+
+                    if matches!(
+                        rule.identifier.matching_strategy,
+                        Some(MatchingStrategy::Regex)
+                    ) {
+                        let re = Regex::new(&rule.identifier.id)?;
+                        regex_identifiers.insert(rule.identifier.id.clone(), re);
+                    }
+                }
+            }
+        }
+
         if let Some(identifiers) = &mut self.object_name_change_applications {
             for identifier in identifiers {
                 if identifier.matching_strategy.is_none() {
@@ -810,6 +1050,9 @@ impl StaticConfig {
             has_pending_raise_op: false,
             pending_move_op: None,
             already_moved_window_handles: Arc::new(Mutex::new(HashSet::new())),
+            history: History::default(),
+            scratchpad: Scratchpad::default(),
+            marks: Marks::default(),
         };
 
         match value.focus_follows_mouse {
@@ -23,6 +23,7 @@ use winput::release;
 use winput::Vk;
 
 use komorebi_core::ApplicationIdentifier;
+use komorebi_core::ContainerConstraints;
 use komorebi_core::HidingBehaviour;
 use komorebi_core::Rect;
 
@@ -40,6 +41,9 @@ use crate::MANAGE_IDENTIFIERS;
 use crate::NO_TITLEBAR;
 use crate::PERMAIGNORE_CLASSES;
 use crate::REGEX_IDENTIFIERS;
+use crate::SCRATCHPAD_IDENTIFIERS;
+use crate::SIZE_CONSTRAINTS;
+use crate::SIZE_CONSTRAINT_RULES;
 use crate::WSL2_UI_PROCESSES;
 
 #[derive(Debug, Clone, Copy, JsonSchema)]
@@ -485,6 +489,79 @@ impl Window {
 
         Ok(false)
     }
+
+    /// The size constraints of this window, which are looked up once when the window is first laid
+    /// out and then cached until it is no longer managed or the rules are reloaded
+    pub fn size_constraints(self) -> Option<ContainerConstraints> {
+        if let Some(constraints) = SIZE_CONSTRAINTS.lock().get(&self.hwnd) {
+            return *constraints;
+        }
+
+        // A window which can't be read, such as one which is closing, is treated as having no
+        // constraints rather than failing the whole layout, and is looked up again next time
+        match self.matching_size_constraints() {
+            Ok(constraints) => {
+                SIZE_CONSTRAINTS.lock().insert(self.hwnd, constraints);
+                constraints
+            }
+            Err(error) => {
+                tracing::warn!(
+                    "could not read the size constraints of {}: {error}",
+                    self.hwnd
+                );
+                None
+            }
+        }
+    }
+
+    /// The size constraints of the first size constraint rule which matches this window
+    fn matching_size_constraints(self) -> Result<Option<ContainerConstraints>> {
+        let size_constraint_rules = SIZE_CONSTRAINT_RULES.lock();
+        if size_constraint_rules.is_empty() {
+            return Ok(None);
+        }
+
+        let (title, exe_name, class) = (self.title()?, self.exe()?, self.class()?);
+        let regex_identifiers = REGEX_IDENTIFIERS.lock();
+
+        Ok(size_constraint_rules
+            .iter()
+            .find(|(identifier, _)| {
+                should_act(
+                    &title,
+                    &exe_name,
+                    &class,
+                    std::slice::from_ref(identifier),
+                    &regex_identifiers,
+                )
+            })
+            .map(|(_, constraints)| *constraints))
+    }
+
+    /// The name of the scratchpad window that this window becomes, which is the id of the first
+    /// scratchpad rule which matches it
+    pub fn scratchpad_name(self) -> Result<Option<String>> {
+        let scratchpad_identifiers = SCRATCHPAD_IDENTIFIERS.lock();
+        if scratchpad_identifiers.is_empty() {
+            return Ok(None);
+        }
+
+        let (title, exe_name, class) = (self.title()?, self.exe()?, self.class()?);
+        let regex_identifiers = REGEX_IDENTIFIERS.lock();
+
+        Ok(scratchpad_identifiers
+            .iter()
+            .find(|identifier| {
+                should_act(
+                    &title,
+                    &exe_name,
+                    &class,
+                    std::slice::from_ref(*identifier),
+                    &regex_identifiers,
+                )
+            })
+            .map(|identifier| identifier.id.clone()))
+    }
 }
 
 fn window_is_eligible(
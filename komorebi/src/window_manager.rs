@@ -20,26 +20,37 @@ use serde::Serialize;
 use uds_windows::UnixListener;
 
 use komorebi_core::config_generation::IdWithIdentifier;
-use komorebi_core::custom_layout::CustomLayout;
-use komorebi_core::Arrangement;
+use komorebi_core::custom_layout::Orientation;
 use komorebi_core::Axis;
+use komorebi_core::BalanceScope;
+use komorebi_core::BspStrategy;
+use komorebi_core::CustomLayout;
 use komorebi_core::CycleDirection;
 use komorebi_core::DefaultLayout;
+use komorebi_core::DirectionStrategy;
 use komorebi_core::FocusFollowsMouseImplementation;
 use komorebi_core::Layout;
+use komorebi_core::LayoutCondition;
 use komorebi_core::MoveBehaviour;
 use komorebi_core::OperationBehaviour;
 use komorebi_core::OperationDirection;
+use komorebi_core::Padding;
 use komorebi_core::Rect;
+use komorebi_core::ResizeRatio;
+use komorebi_core::SizeLimit;
 use komorebi_core::Sizing;
 use komorebi_core::WindowContainerBehaviour;
 
 use crate::border::Border;
 use crate::container::Container;
 use crate::current_virtual_desktop;
+use crate::history::History;
 use crate::load_configuration;
+use crate::marks::Marks;
 use crate::monitor::Monitor;
 use crate::ring::Ring;
+use crate::scratchpad::Scratchpad;
+use crate::snapshot::Snapshot;
 use crate::static_config::StaticConfig;
 use crate::window::Window;
 use crate::window_manager_event::WindowManagerEvent;
@@ -81,12 +92,17 @@ pub struct WindowManager {
     pub has_pending_raise_op: bool,
     pub pending_move_op: Option<(usize, usize, usize)>,
     pub already_moved_window_handles: Arc<Mutex<HashSet<isize>>>,
+    pub history: History,
+    pub scratchpad: Scratchpad,
+    pub marks: Marks,
 }
 
 #[allow(clippy::struct_excessive_bools)]
 #[derive(Debug, Serialize, JsonSchema)]
 pub struct State {
     pub monitors: Ring<Monitor>,
+    pub scratchpad: Scratchpad,
+    pub marks: Marks,
     pub is_paused: bool,
     pub invisible_borders: Rect,
     pub resize_delta: i32,
@@ -117,6 +133,8 @@ impl From<&WindowManager> for State {
     fn from(wm: &WindowManager) -> Self {
         Self {
             monitors: wm.monitors.clone(),
+            scratchpad: wm.scratchpad.clone(),
+            marks: wm.marks.clone(),
             is_paused: wm.is_paused,
             invisible_borders: wm.invisible_borders,
             work_area_offset: wm.work_area_offset,
@@ -207,6 +225,9 @@ impl WindowManager {
             has_pending_raise_op: false,
             pending_move_op: None,
             already_moved_window_handles: Arc::new(Mutex::new(HashSet::new())),
+            history: History::default(),
+            scratchpad: Scratchpad::default(),
+            marks: Marks::default(),
         })
     }
 
@@ -464,8 +485,11 @@ impl WindowManager {
                             workspace.set_layout(cached_workspace.layout().clone());
                             workspace.set_layout_rules(cached_workspace.layout_rules().clone());
                             workspace.set_layout_flip(cached_workspace.layout_flip());
+                            workspace.set_layout_options(cached_workspace.layout_options());
+                            workspace.set_direction_strategy(cached_workspace.direction_strategy());
                             workspace.set_workspace_padding(cached_workspace.workspace_padding());
                             workspace.set_container_padding(cached_workspace.container_padding());
+                            workspace.set_smart_gaps(cached_workspace.smart_gaps());
                         }
                     }
                 }
@@ -640,6 +664,7 @@ impl WindowManager {
 
         for monitor in self.monitors_mut() {
             let work_area = *monitor.work_area_size();
+            let identifiers = monitor.identifiers();
             let offset = if monitor.work_area_offset().is_some() {
                 monitor.work_area_offset()
             } else {
@@ -657,12 +682,161 @@ impl WindowManager {
                 }
             }
 
-            workspace.update(&work_area, offset, &invisible_borders)?;
+            workspace.update(&work_area, offset, &invisible_borders, &identifiers)?;
         }
 
         Ok(())
     }
 
+    #[tracing::instrument(skip(self))]
+    pub fn undo(&mut self) -> Result<()> {
+        tracing::info!("undoing the last change to the arrangement");
+
+        let previous = self
+            .history
+            .undo(&self.monitors)
+            .ok_or_else(|| anyhow!("there are no changes to undo"))?;
+
+        self.reclaim_scratchpad_windows(&previous)?;
+        Snapshot::from(&previous).restore(self)
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub fn redo(&mut self) -> Result<()> {
+        tracing::info!("redoing the last undone change to the arrangement");
+
+        let next = self
+            .history
+            .redo(&self.monitors)
+            .ok_or_else(|| anyhow!("there are no undone changes to redo"))?;
+
+        self.reclaim_scratchpad_windows(&next)?;
+        Snapshot::from(&next).restore(self)
+    }
+
+    /// Takes the windows which were sent to the scratchpad after an arrangement out of it again
+    /// and tiles them on the focused workspace, so that restoring the arrangement can match them
+    fn reclaim_scratchpad_windows(&mut self, arrangement: &Ring<Monitor>) -> Result<()> {
+        let windows = self.scratchpad.take_windows(|hwnd| {
+            arrangement.elements().iter().any(|monitor| {
+                monitor
+                    .workspaces()
+                    .iter()
+                    .any(|workspace| workspace.contains_window(hwnd))
+            })
+        });
+
+        for window in windows {
+            window.restore();
+
+            let mut container = Container::default();
+            container.add_window(window);
+            self.focused_workspace_mut()?.add_container(container);
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub fn balance(&mut self, scope: BalanceScope) -> Result<()> {
+        tracing::info!("balancing workspace");
+
+        let area = self.focused_workspace_layout_area()?;
+        let work_area = self.focused_monitor_work_area()?;
+        let identifiers = self
+            .focused_monitor()
+            .ok_or_else(|| anyhow!("there is no monitor"))?
+            .identifiers();
+        let workspace = self.focused_workspace_mut()?;
+        let focused_idx = workspace.focused_container_idx();
+
+        // Columns and rows are found on the layout without any resize adjustments, so that they
+        // are the same however the containers have been resized
+        let unaltered =
+            NonZeroUsize::new(workspace.containers().len()).map_or_else(Vec::new, |len| {
+                workspace.layout().as_boxed_arrangement().calculate(
+                    &area,
+                    len,
+                    None,
+                    workspace.layout_flip(),
+                    workspace.layout_options(),
+                    workspace.scroll_offset(),
+                    &[],
+                )
+            });
+
+        let focused = unaltered.get(focused_idx).copied();
+        let in_column = |idx: usize| {
+            focused
+                .zip(unaltered.get(idx))
+                .is_some_and(|(focused, rect)| {
+                    rect.left == focused.left && rect.right == focused.right
+                })
+        };
+        let in_row = |idx: usize| {
+            focused
+                .zip(unaltered.get(idx))
+                .is_some_and(|(focused, rect)| {
+                    rect.top == focused.top && rect.bottom == focused.bottom
+                })
+        };
+
+        for (idx, resize) in workspace.resize_dimensions_mut().iter_mut().enumerate() {
+            if let Some(ratio) = resize {
+                let (horizontal, vertical) = match scope {
+                    BalanceScope::Workspace => (true, true),
+                    BalanceScope::Column => (false, in_column(idx)),
+                    BalanceScope::Row => (in_row(idx), false),
+                    BalanceScope::Horizontal => (true, false),
+                    BalanceScope::Vertical => (false, true),
+                };
+
+                if horizontal {
+                    ratio.left = 0.0;
+                    ratio.right = 0.0;
+                }
+
+                if vertical {
+                    ratio.top = 0.0;
+                    ratio.bottom = 0.0;
+                }
+
+                if *ratio == ResizeRatio::default() {
+                    *resize = None;
+                }
+            }
+        }
+
+        // The columns of a custom layout are resized by changing their widths, which run along
+        // the axis that the columns are laid out on
+        let balances_columns = |custom: &CustomLayout| match custom.orientation() {
+            Orientation::Columns => matches!(
+                scope,
+                BalanceScope::Workspace | BalanceScope::Row | BalanceScope::Horizontal
+            ),
+            Orientation::Rows => matches!(
+                scope,
+                BalanceScope::Workspace | BalanceScope::Column | BalanceScope::Vertical
+            ),
+        };
+
+        if let Layout::Custom(custom) = workspace.layout_mut() {
+            if balances_columns(custom) {
+                custom.reset_column_widths();
+            }
+        }
+
+        if let Some((_, Layout::Custom(custom))) =
+            workspace.active_layout_rule_mut(&work_area, &identifiers)
+        {
+            if balances_columns(custom) {
+                custom.reset_column_widths();
+            }
+        }
+
+        self.update_focused_workspace(false)
+    }
+
     #[tracing::instrument(skip(self))]
     pub fn manage_focused_window(&mut self) -> Result<()> {
         let hwnd = WindowsApi::foreground_window()?;
@@ -876,86 +1050,91 @@ impl WindowManager {
         delta: i32,
         update: bool,
     ) -> Result<()> {
-        let work_area = self.focused_monitor_work_area()?;
+        let area = self.focused_workspace_layout_area()?;
         let workspace = self.focused_workspace_mut()?;
 
-        match workspace.layout() {
-            Layout::Default(layout) => {
-                tracing::info!("resizing window");
-                let len = NonZeroUsize::new(workspace.containers().len())
-                    .ok_or_else(|| anyhow!("there must be at least one container"))?;
-                let focused_idx = workspace.focused_container_idx();
-                let focused_idx_resize = workspace
-                    .resize_dimensions()
-                    .get(focused_idx)
-                    .ok_or_else(|| anyhow!("there is no resize adjustment for this container"))?;
-
-                if direction
-                    .destination(
-                        workspace.layout().as_boxed_direction().as_ref(),
-                        workspace.layout_flip(),
-                        focused_idx,
-                        len,
-                    )
-                    .is_some()
-                {
-                    let unaltered = layout.calculate(
-                        &work_area,
-                        len,
-                        workspace.container_padding(),
-                        workspace.layout_flip(),
-                        &[],
-                    );
+        // Containers in a tree of splits are always sized by the ratios of the tree
+        if matches!(workspace.layout(), Layout::Tree(_)) {
+            bail!("cannot resize containers in a tree layout, change its ratios instead");
+        }
 
-                    let mut direction = direction;
-
-                    // We only ever want to operate on the unflipped Rect positions when resizing, then we
-                    // can flip them however they need to be flipped once the resizing has been done
-                    if let Some(flip) = workspace.layout_flip() {
-                        match flip {
-                            Axis::Horizontal => {
-                                if matches!(direction, OperationDirection::Left)
-                                    || matches!(direction, OperationDirection::Right)
-                                {
-                                    direction = direction.opposite();
-                                }
-                            }
-                            Axis::Vertical => {
-                                if matches!(direction, OperationDirection::Up)
-                                    || matches!(direction, OperationDirection::Down)
-                                {
-                                    direction = direction.opposite();
-                                }
-                            }
-                            Axis::HorizontalAndVertical => direction = direction.opposite(),
+        tracing::info!("resizing window");
+        let len = NonZeroUsize::new(workspace.containers().len())
+            .ok_or_else(|| anyhow!("there must be at least one container"))?;
+        let focused_idx = workspace.focused_container_idx();
+        let focused_idx_resize = *workspace
+            .resize_dimensions()
+            .get(focused_idx)
+            .ok_or_else(|| anyhow!("there is no resize adjustment for this container"))?;
+
+        if direction
+            .destination(
+                workspace.layout().as_boxed_direction().as_ref(),
+                workspace.layout_flip(),
+                workspace.layout_options(),
+                focused_idx,
+                len,
+            )
+            .is_some()
+        {
+            let unaltered = workspace.layout().as_boxed_arrangement().calculate(
+                &area,
+                len,
+                workspace.layout_container_padding(),
+                workspace.layout_flip(),
+                workspace.layout_options(),
+                workspace.scroll_offset(),
+                &[],
+            );
+
+            let mut direction = direction;
+
+            // We only ever want to operate on the unflipped Rect positions when resizing, then we
+            // can flip them however they need to be flipped once the resizing has been done
+            if let Some(flip) = workspace.layout_flip() {
+                match flip {
+                    Axis::Horizontal => {
+                        if matches!(direction, OperationDirection::Left)
+                            || matches!(direction, OperationDirection::Right)
+                        {
+                            direction = direction.opposite();
                         }
                     }
+                    Axis::Vertical => {
+                        if matches!(direction, OperationDirection::Up)
+                            || matches!(direction, OperationDirection::Down)
+                        {
+                            direction = direction.opposite();
+                        }
+                    }
+                    Axis::HorizontalAndVertical => direction = direction.opposite(),
+                }
+            }
 
-                    let resize = layout.resize(
-                        unaltered
-                            .get(focused_idx)
-                            .ok_or_else(|| anyhow!("there is no last layout"))?,
-                        focused_idx_resize,
-                        direction,
-                        sizing,
-                        delta,
-                    );
-
-                    workspace.resize_dimensions_mut()[focused_idx] = resize;
+            let resize = workspace.layout().resize(
+                unaltered
+                    .get(focused_idx)
+                    .ok_or_else(|| anyhow!("there is no last layout"))?,
+                &focused_idx_resize.map(|r| r.to_pixels(&area)),
+                direction,
+                sizing,
+                delta,
+            );
 
-                    return if update {
-                        self.update_focused_workspace(false)
-                    } else {
-                        Ok(())
-                    };
-                }
+            // Resize adjustments are stored as ratios of the layout area so that they
+            // still have the same proportions if the area changes
+            workspace.resize_dimensions_mut()[focused_idx] =
+                resize.map(|r| ResizeRatio::from_pixels(&r, &area));
 
-                tracing::warn!("cannot resize container in this direction");
-            }
-            Layout::Custom(_) => {
-                tracing::warn!("containers cannot be resized when using custom layouts");
-            }
+            return if update {
+                self.update_focused_workspace(false)
+            } else {
+                Ok(())
+            };
         }
+
+        tracing::warn!("cannot resize container in this direction");
+
         Ok(())
     }
 
@@ -979,6 +1158,10 @@ impl WindowManager {
             }
         }
 
+        for entry in self.scratchpad.windows() {
+            entry.window.restore();
+        }
+
         Ok(())
     }
 
@@ -1425,24 +1608,9 @@ impl WindowManager {
         tracing::info!("adding window to container");
 
         let workspace = self.focused_workspace_mut()?;
-        let len = NonZeroUsize::new(workspace.containers_mut().len())
-            .ok_or_else(|| anyhow!("there must be at least one container"))?;
         let current_container_idx = workspace.focused_container_idx();
 
-        let is_valid = direction
-            .destination(
-                workspace.layout().as_boxed_direction().as_ref(),
-                workspace.layout_flip(),
-                workspace.focused_container_idx(),
-                len,
-            )
-            .is_some();
-
-        if is_valid {
-            let new_idx = workspace.new_idx_for_direction(direction).ok_or_else(|| {
-                anyhow!("this is not a valid direction from the current position")
-            })?;
-
+        if let Some(new_idx) = workspace.new_idx_for_direction(direction) {
             let adjusted_new_index = if new_idx > current_container_idx {
                 new_idx - 1
             } else {
@@ -1475,7 +1643,7 @@ impl WindowManager {
 
         let workspace = self.focused_workspace_mut()?;
         let target_idx = match workspace.layout() {
-            Layout::Default(_) => 0,
+            Layout::Default(_) | Layout::Tree(_) => 0,
             Layout::Custom(custom) => custom
                 .first_container_idx(custom.primary_idx().map_or(0, |primary_idx| primary_idx)),
         };
@@ -1558,6 +1726,279 @@ impl WindowManager {
         workspace.new_container_for_floating_window()
     }
 
+    #[tracing::instrument(skip(self))]
+    pub fn send_to_scratchpad(&mut self, name: Option<String>) -> Result<()> {
+        let hwnd = WindowsApi::foreground_window()?;
+        if self.scratchpad.contains(hwnd) {
+            return self.hide_scratchpad_window();
+        }
+
+        tracing::info!("sending window to the scratchpad");
+
+        let window = Window { hwnd };
+        let workspace = self.focused_workspace_mut()?;
+        if !workspace.contains_window(hwnd) {
+            bail!("the focused window is not managed on the focused workspace");
+        }
+
+        workspace.remove_window(hwnd)?;
+
+        let name = match name {
+            None => window.exe()?,
+            Some(name) => name,
+        };
+
+        window.hide();
+        self.scratchpad.add(name, window);
+        self.update_focused_workspace(self.mouse_follows_focus)
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub fn toggle_scratchpad(&mut self, name: &str) -> Result<()> {
+        let idx = self
+            .scratchpad
+            .idx_for_name(name)
+            .ok_or_else(|| anyhow!("there is no scratchpad window named {name}"))?;
+
+        if self.scratchpad.shown() == Option::from(idx) {
+            self.hide_scratchpad_window()
+        } else {
+            self.show_scratchpad_window(idx)
+        }
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub fn cycle_scratchpad(&mut self, direction: CycleDirection) -> Result<()> {
+        let len = NonZeroUsize::new(self.scratchpad.windows().len())
+            .ok_or_else(|| anyhow!("there are no windows in the scratchpad"))?;
+
+        let idx = self
+            .scratchpad
+            .shown()
+            .map_or(0, |shown| direction.next_idx(shown, len));
+
+        self.show_scratchpad_window(idx)
+    }
+
+    /// Shows a scratchpad window floating in the middle of the focused monitor, after hiding the
+    /// scratchpad window which was shown before it
+    fn show_scratchpad_window(&mut self, idx: usize) -> Result<()> {
+        tracing::info!("showing scratchpad window");
+
+        if let Some(shown) = self.scratchpad.shown() {
+            if shown != idx {
+                if let Some(entry) = self.scratchpad.windows().get(shown) {
+                    entry.window.hide();
+                }
+            }
+        }
+
+        let work_area = self.focused_monitor_work_area()?;
+        let invisible_borders = self.invisible_borders;
+
+        let mut window = self
+            .scratchpad
+            .windows()
+            .get(idx)
+            .ok_or_else(|| anyhow!("there is no scratchpad window at this index"))?
+            .window;
+
+        window.restore();
+        window.center(&work_area, &invisible_borders)?;
+        window.focus(self.mouse_follows_focus)?;
+
+        self.scratchpad.set_shown(Option::from(idx));
+
+        Ok(())
+    }
+
+    /// Hides the scratchpad window which is shown and gives focus back to the focused workspace
+    fn hide_scratchpad_window(&mut self) -> Result<()> {
+        tracing::info!("hiding scratchpad window");
+
+        if let Some(shown) = self.scratchpad.shown() {
+            if let Some(entry) = self.scratchpad.windows().get(shown) {
+                entry.window.hide();
+            }
+
+            self.scratchpad.set_shown(None);
+        }
+
+        self.update_focused_workspace(self.mouse_follows_focus)
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub fn mark_focused_window(&mut self, mark: String) -> Result<()> {
+        let hwnd = WindowsApi::foreground_window()?;
+        if !self.focused_workspace()?.contains_window(hwnd) {
+            bail!("the focused window is not managed on the focused workspace");
+        }
+
+        tracing::info!("marking window");
+
+        self.marks.mark(mark, hwnd);
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub fn unmark(&mut self, mark: &str) -> Result<()> {
+        tracing::info!("removing mark");
+
+        self.marks
+            .unmark(mark)
+            .ok_or_else(|| anyhow!("there is no window marked {mark}"))?;
+
+        Ok(())
+    }
+
+    /// The monitor and workspace of a window, whether it is tiled, floating, in a monocle container
+    /// or maximized
+    fn workspace_location_for_window(&self, hwnd: isize) -> Option<(usize, usize)> {
+        for (monitor_idx, monitor) in self.monitors().iter().enumerate() {
+            for (workspace_idx, workspace) in monitor.workspaces().iter().enumerate() {
+                if workspace.contains_window(hwnd) {
+                    return Option::from((monitor_idx, workspace_idx));
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Takes the labels off of windows which are no longer on any workspace or in the scratchpad
+    pub fn prune_marks(&mut self) {
+        let monitors = &self.monitors;
+        let scratchpad = &self.scratchpad;
+
+        self.marks.retain_managed(|hwnd| {
+            scratchpad.contains(hwnd)
+                || monitors.elements().iter().any(|monitor| {
+                    monitor
+                        .workspaces()
+                        .iter()
+                        .any(|workspace| workspace.contains_window(hwnd))
+                })
+        });
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub fn focus_mark(&mut self, mark: &str) -> Result<()> {
+        let hwnd = self
+            .marks
+            .window(mark)
+            .ok_or_else(|| anyhow!("there is no window marked {mark}"))?;
+
+        tracing::info!("focusing marked window");
+
+        if let Some(idx) = self
+            .scratchpad
+            .windows()
+            .iter()
+            .position(|entry| entry.window.hwnd == hwnd)
+        {
+            return self.show_scratchpad_window(idx);
+        }
+
+        let (monitor_idx, workspace_idx) = self
+            .workspace_location_for_window(hwnd)
+            .ok_or_else(|| anyhow!("the window marked {mark} is not on any workspace"))?;
+
+        if self.focused_monitor_idx() != monitor_idx {
+            self.focus_monitor(monitor_idx)?;
+        }
+
+        if self.focused_workspace_idx_for_monitor_idx(monitor_idx)? != workspace_idx {
+            self.focus_workspace(workspace_idx)?;
+        }
+
+        let workspace = self.focused_workspace_mut()?;
+
+        // Floating, monocle and maximized windows aren't in any of the tiled containers
+        if workspace.container_idx_for_window(hwnd).is_some() {
+            workspace.focus_container_by_window(hwnd)?;
+            self.update_focused_workspace(self.mouse_follows_focus)
+        } else {
+            Window { hwnd }.focus(self.mouse_follows_focus)
+        }
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub fn swap_with_mark(&mut self, mark: &str) -> Result<()> {
+        self.handle_unmanaged_window_behaviour()?;
+
+        let hwnd = self
+            .marks
+            .window(mark)
+            .ok_or_else(|| anyhow!("there is no window marked {mark}"))?;
+
+        let (target_monitor_idx, target_workspace_idx) =
+            self.workspace_location_for_window(hwnd)
+                .ok_or_else(|| anyhow!("the window marked {mark} is not on any workspace"))?;
+
+        let target_container_idx = self
+            .monitors()
+            .get(target_monitor_idx)
+            .and_then(|monitor| monitor.workspaces().get(target_workspace_idx))
+            .and_then(|workspace| workspace.container_idx_for_window(hwnd))
+            .ok_or_else(|| anyhow!("the window marked {mark} is not in a tiled container"))?;
+
+        let origin_monitor_idx = self.focused_monitor_idx();
+        let origin_workspace_idx =
+            self.focused_workspace_idx_for_monitor_idx(origin_monitor_idx)?;
+        let origin_container_idx = self.focused_workspace()?.focused_container_idx();
+
+        if self.focused_container()?.contains_window(hwnd) {
+            bail!("the window marked {mark} is already in the focused container");
+        }
+
+        tracing::info!("swapping with marked window");
+
+        if (origin_monitor_idx, origin_workspace_idx) == (target_monitor_idx, target_workspace_idx)
+        {
+            self.focused_workspace_mut()?
+                .swap_containers(origin_container_idx, target_container_idx);
+        } else {
+            self.swap_containers(
+                (
+                    origin_monitor_idx,
+                    origin_workspace_idx,
+                    origin_container_idx,
+                ),
+                (
+                    target_monitor_idx,
+                    target_workspace_idx,
+                    target_container_idx,
+                ),
+            )?;
+
+            self.focused_workspace_mut()?
+                .focus_container(origin_container_idx);
+
+            // The container which was swapped away has to be hidden if it is now on a workspace
+            // which isn't visible, or retiled if it is on a workspace on another monitor
+            let invisible_borders = self.invisible_borders;
+            let offset = self.work_area_offset;
+            let mouse_follows_focus = self.mouse_follows_focus;
+
+            if target_monitor_idx != origin_monitor_idx {
+                let target_monitor = self
+                    .monitors_mut()
+                    .get_mut(target_monitor_idx)
+                    .ok_or_else(|| anyhow!("there is no monitor at this index"))?;
+
+                target_monitor.load_focused_workspace(mouse_follows_focus)?;
+                target_monitor.update_focused_workspace(offset, &invisible_borders)?;
+            }
+
+            self.focused_monitor_mut()
+                .ok_or_else(|| anyhow!("there is no monitor"))?
+                .load_focused_workspace(mouse_follows_focus)?;
+        }
+
+        self.update_focused_workspace(self.mouse_follows_focus)
+    }
+
     #[tracing::instrument(skip(self))]
     pub fn toggle_monocle(&mut self) -> Result<()> {
         self.handle_unmanaged_window_behaviour()?;
@@ -1668,7 +2109,7 @@ impl WindowManager {
         let workspace = self.focused_workspace_mut()?;
 
         match workspace.layout() {
-            Layout::Default(_) => {}
+            Layout::Default(_) | Layout::Tree(_) => {}
             Layout::Custom(layout) => {
                 let primary_idx =
                     layout.first_container_idx(layout.primary_idx().ok_or_else(|| {
@@ -1703,7 +2144,7 @@ impl WindowManager {
                 tracing::info!("next layout: {new_layout}");
                 workspace.set_layout(Layout::Default(new_layout));
             }
-            Layout::Custom(_) => {}
+            Layout::Custom(_) | Layout::Tree(_) => {}
         }
 
         self.update_focused_workspace(self.mouse_follows_focus)
@@ -1716,13 +2157,13 @@ impl WindowManager {
     {
         tracing::info!("changing layout");
 
-        let layout = CustomLayout::from_path(path)?;
+        let layout = Layout::from_custom_path(path)?;
         let workspace = self.focused_workspace_mut()?;
 
-        match workspace.layout() {
-            Layout::Default(_) => {
+        match (workspace.layout(), &layout) {
+            (Layout::Default(_), Layout::Custom(custom)) => {
                 let primary_idx =
-                    layout.first_container_idx(layout.primary_idx().ok_or_else(|| {
+                    custom.first_container_idx(custom.primary_idx().ok_or_else(|| {
                         anyhow!("this custom layout does not have a primary column")
                     })?);
 
@@ -1731,10 +2172,10 @@ impl WindowManager {
                     workspace.swap_containers(0, primary_idx);
                 }
             }
-            Layout::Custom(_) => {}
+            _ => {}
         }
 
-        workspace.set_layout(Layout::Custom(layout));
+        workspace.set_layout(layout);
         workspace.set_layout_flip(None);
         self.update_focused_workspace(self.mouse_follows_focus)
     }
@@ -1749,7 +2190,7 @@ impl WindowManager {
             .workspace_padding()
             .ok_or_else(|| anyhow!("there is no workspace padding"))?;
 
-        workspace.set_workspace_padding(Option::from(sizing.adjust_by(padding, adjustment)));
+        workspace.set_workspace_padding(Option::from(padding.adjusted_by(sizing, adjustment)));
 
         self.update_focused_workspace(false)
     }
@@ -1764,7 +2205,43 @@ impl WindowManager {
             .container_padding()
             .ok_or_else(|| anyhow!("there is no container padding"))?;
 
-        workspace.set_container_padding(Option::from(sizing.adjust_by(padding, adjustment)));
+        workspace.set_container_padding(Option::from(padding.adjusted_by(sizing, adjustment)));
+
+        self.update_focused_workspace(false)
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub fn adjust_main_ratio(&mut self, sizing: Sizing, adjustment: f32) -> Result<()> {
+        tracing::info!("adjusting main ratio");
+
+        let workspace = self.focused_workspace_mut()?;
+        workspace
+            .layout_options_mut()
+            .adjust_main_ratio(sizing, adjustment);
+
+        self.update_focused_workspace(false)
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub fn adjust_master_count(&mut self, sizing: Sizing, adjustment: usize) -> Result<()> {
+        tracing::info!("adjusting master count");
+
+        let workspace = self.focused_workspace_mut()?;
+        workspace
+            .layout_options_mut()
+            .adjust_master_count(sizing, adjustment);
+
+        self.update_focused_workspace(false)
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub fn adjust_column_ratio(&mut self, sizing: Sizing, adjustment: f32) -> Result<()> {
+        tracing::info!("adjusting column ratio");
+
+        let workspace = self.focused_workspace_mut()?;
+        workspace
+            .layout_options_mut()
+            .adjust_column_ratio(sizing, adjustment);
 
         self.update_focused_workspace(false)
     }
@@ -1791,6 +2268,74 @@ impl WindowManager {
         self.update_focused_workspace(false)
     }
 
+    #[tracing::instrument(skip(self))]
+    pub fn set_workspace_direction_strategy(
+        &mut self,
+        monitor_idx: usize,
+        workspace_idx: usize,
+        direction_strategy: DirectionStrategy,
+    ) -> Result<()> {
+        let monitor = self
+            .monitors_mut()
+            .get_mut(monitor_idx)
+            .ok_or_else(|| anyhow!("there is no monitor"))?;
+
+        let workspace = monitor
+            .workspaces_mut()
+            .get_mut(workspace_idx)
+            .ok_or_else(|| anyhow!("there is no workspace"))?;
+
+        workspace.set_direction_strategy(direction_strategy);
+
+        self.update_focused_workspace(false)
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub fn set_workspace_bsp_strategy(
+        &mut self,
+        monitor_idx: usize,
+        workspace_idx: usize,
+        bsp_strategy: BspStrategy,
+    ) -> Result<()> {
+        let monitor = self
+            .monitors_mut()
+            .get_mut(monitor_idx)
+            .ok_or_else(|| anyhow!("there is no monitor"))?;
+
+        let workspace = monitor
+            .workspaces_mut()
+            .get_mut(workspace_idx)
+            .ok_or_else(|| anyhow!("there is no workspace"))?;
+
+        workspace.layout_options_mut().bsp_strategy = bsp_strategy;
+
+        self.update_focused_workspace(false)
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub fn set_workspace_max_size(
+        &mut self,
+        monitor_idx: usize,
+        workspace_idx: usize,
+        max_width: Option<SizeLimit>,
+        max_height: Option<SizeLimit>,
+    ) -> Result<()> {
+        let monitor = self
+            .monitors_mut()
+            .get_mut(monitor_idx)
+            .ok_or_else(|| anyhow!("there is no monitor"))?;
+
+        let workspace = monitor
+            .workspaces_mut()
+            .get_mut(workspace_idx)
+            .ok_or_else(|| anyhow!("there is no workspace"))?;
+
+        workspace.set_max_width(max_width);
+        workspace.set_max_height(max_height);
+
+        self.update_focused_workspace(false)
+    }
+
     #[tracing::instrument(skip(self))]
     pub fn add_workspace_layout_default_rule(
         &mut self,
@@ -1811,6 +2356,7 @@ impl WindowManager {
             .ok_or_else(|| anyhow!("there is no monitor"))?;
 
         let work_area = *monitor.work_area_size();
+        let identifiers = monitor.identifiers();
         let focused_workspace_idx = monitor.focused_workspace_idx();
         let offset = if monitor.work_area_offset().is_some() {
             monitor.work_area_offset()
@@ -1823,14 +2369,15 @@ impl WindowManager {
             .get_mut(workspace_idx)
             .ok_or_else(|| anyhow!("there is no monitor"))?;
 
-        let rules: &mut Vec<(usize, Layout)> = workspace.layout_rules_mut();
-        rules.retain(|pair| pair.0 != at_container_count);
-        rules.push((at_container_count, Layout::Default(layout)));
-        rules.sort_by(|a, b| a.0.cmp(&b.0));
+        let condition = LayoutCondition::from(at_container_count);
+        let rules: &mut Vec<(LayoutCondition, Layout)> = workspace.layout_rules_mut();
+        rules.retain(|pair| pair.0 != condition);
+        rules.push((condition, Layout::Default(layout)));
+        rules.sort_by_key(|rule| rule.0.containers);
 
         // If this is the focused workspace on a non-focused screen, let's update it
         if focused_monitor_idx != monitor_idx && focused_workspace_idx == workspace_idx {
-            workspace.update(&work_area, offset, &invisible_borders)?;
+            workspace.update(&work_area, offset, &invisible_borders, &identifiers)?;
             Ok(())
         } else {
             Ok(self.update_focused_workspace(false)?)
@@ -1860,6 +2407,7 @@ impl WindowManager {
             .ok_or_else(|| anyhow!("there is no monitor"))?;
 
         let work_area = *monitor.work_area_size();
+        let identifiers = monitor.identifiers();
         let focused_workspace_idx = monitor.focused_workspace_idx();
         let offset = if monitor.work_area_offset().is_some() {
             monitor.work_area_offset()
@@ -1872,16 +2420,17 @@ impl WindowManager {
             .get_mut(workspace_idx)
             .ok_or_else(|| anyhow!("there is no monitor"))?;
 
-        let layout = CustomLayout::from_path(path)?;
+        let layout = Layout::from_custom_path(path)?;
 
-        let rules: &mut Vec<(usize, Layout)> = workspace.layout_rules_mut();
-        rules.retain(|pair| pair.0 != at_container_count);
-        rules.push((at_container_count, Layout::Custom(layout)));
-        rules.sort_by(|a, b| a.0.cmp(&b.0));
+        let condition = LayoutCondition::from(at_container_count);
+        let rules: &mut Vec<(LayoutCondition, Layout)> = workspace.layout_rules_mut();
+        rules.retain(|pair| pair.0 != condition);
+        rules.push((condition, layout));
+        rules.sort_by_key(|rule| rule.0.containers);
 
         // If this is the focused workspace on a non-focused screen, let's update it
         if focused_monitor_idx != monitor_idx && focused_workspace_idx == workspace_idx {
-            workspace.update(&work_area, offset, &invisible_borders)?;
+            workspace.update(&work_area, offset, &invisible_borders, &identifiers)?;
             Ok(())
         } else {
             Ok(self.update_focused_workspace(false)?)
@@ -1906,6 +2455,7 @@ impl WindowManager {
             .ok_or_else(|| anyhow!("there is no monitor"))?;
 
         let work_area = *monitor.work_area_size();
+        let identifiers = monitor.identifiers();
         let focused_workspace_idx = monitor.focused_workspace_idx();
         let offset = if monitor.work_area_offset().is_some() {
             monitor.work_area_offset()
@@ -1918,12 +2468,12 @@ impl WindowManager {
             .get_mut(workspace_idx)
             .ok_or_else(|| anyhow!("there is no monitor"))?;
 
-        let rules: &mut Vec<(usize, Layout)> = workspace.layout_rules_mut();
+        let rules: &mut Vec<(LayoutCondition, Layout)> = workspace.layout_rules_mut();
         rules.clear();
 
         // If this is the focused workspace on a non-focused screen, let's update it
         if focused_monitor_idx != monitor_idx && focused_workspace_idx == workspace_idx {
-            workspace.update(&work_area, offset, &invisible_borders)?;
+            workspace.update(&work_area, offset, &invisible_borders, &identifiers)?;
             Ok(())
         } else {
             Ok(self.update_focused_workspace(false)?)
@@ -1949,6 +2499,7 @@ impl WindowManager {
             .ok_or_else(|| anyhow!("there is no monitor"))?;
 
         let work_area = *monitor.work_area_size();
+        let identifiers = monitor.identifiers();
         let focused_workspace_idx = monitor.focused_workspace_idx();
         let offset = if monitor.work_area_offset().is_some() {
             monitor.work_area_offset()
@@ -1965,7 +2516,7 @@ impl WindowManager {
 
         // If this is the focused workspace on a non-focused screen, let's update it
         if focused_monitor_idx != monitor_idx && focused_workspace_idx == workspace_idx {
-            workspace.update(&work_area, offset, &invisible_borders)?;
+            workspace.update(&work_area, offset, &invisible_borders, &identifiers)?;
             Ok(())
         } else {
             Ok(self.update_focused_workspace(false)?)
@@ -1983,7 +2534,7 @@ impl WindowManager {
         P: AsRef<Path> + std::fmt::Debug,
     {
         tracing::info!("setting workspace layout");
-        let layout = CustomLayout::from_path(path)?;
+        let layout = Layout::from_custom_path(path)?;
         let invisible_borders = self.invisible_borders;
         let offset = self.work_area_offset;
         let focused_monitor_idx = self.focused_monitor_idx();
@@ -1994,6 +2545,7 @@ impl WindowManager {
             .ok_or_else(|| anyhow!("there is no monitor"))?;
 
         let work_area = *monitor.work_area_size();
+        let identifiers = monitor.identifiers();
         let focused_workspace_idx = monitor.focused_workspace_idx();
         let offset = if monitor.work_area_offset().is_some() {
             monitor.work_area_offset()
@@ -2006,12 +2558,12 @@ impl WindowManager {
             .get_mut(workspace_idx)
             .ok_or_else(|| anyhow!("there is no monitor"))?;
 
-        workspace.set_layout(Layout::Custom(layout));
+        workspace.set_layout(layout);
         workspace.set_layout_flip(None);
 
         // If this is the focused workspace on a non-focused screen, let's update it
         if focused_monitor_idx != monitor_idx && focused_workspace_idx == workspace_idx {
-            workspace.update(&work_area, offset, &invisible_borders)?;
+            workspace.update(&work_area, offset, &invisible_borders, &identifiers)?;
             Ok(())
         } else {
             Ok(self.update_focused_workspace(false)?)
@@ -2065,7 +2617,7 @@ impl WindowManager {
         &mut self,
         monitor_idx: usize,
         workspace_idx: usize,
-        size: i32,
+        size: Padding,
     ) -> Result<()> {
         tracing::info!("setting workspace padding");
 
@@ -2114,7 +2666,7 @@ impl WindowManager {
         &mut self,
         monitor_idx: usize,
         workspace_idx: usize,
-        size: i32,
+        size: Padding,
     ) -> Result<()> {
         tracing::info!("setting container padding");
 
@@ -2147,6 +2699,22 @@ impl WindowManager {
             .work_area_size())
     }
 
+    pub fn focused_workspace_layout_area(&self) -> Result<Rect> {
+        let monitor = self
+            .focused_monitor()
+            .ok_or_else(|| anyhow!("there is no monitor"))?;
+
+        let offset = if monitor.work_area_offset().is_some() {
+            monitor.work_area_offset()
+        } else {
+            self.work_area_offset
+        };
+
+        Ok(self
+            .focused_workspace()?
+            .layout_area(monitor.work_area_size(), offset))
+    }
+
     #[tracing::instrument(skip(self))]
     pub fn focus_monitor(&mut self, idx: usize) -> Result<()> {
         tracing::info!("focusing monitor");
@@ -1,8 +1,10 @@
 use std::collections::VecDeque;
 use std::num::NonZeroUsize;
+use std::ops::Range;
 use std::sync::atomic::Ordering;
 
 use color_eyre::eyre::anyhow;
+use color_eyre::eyre::bail;
 use color_eyre::Result;
 use getset::CopyGetters;
 use getset::Getters;
@@ -11,22 +13,39 @@ use getset::Setters;
 use schemars::JsonSchema;
 use serde::Serialize;
 
+use komorebi_core::arrangement::check_layouts;
+use komorebi_core::arrangement::grid_dimensions;
+use komorebi_core::custom_layout::ColumnSplit;
+use komorebi_core::custom_layout::Orientation;
+use komorebi_core::direction::Geometric;
+use komorebi_core::scroll_offset;
 use komorebi_core::Axis;
-use komorebi_core::CustomLayout;
+use komorebi_core::BspStrategy;
+use komorebi_core::ContainerConstraints;
 use komorebi_core::CycleDirection;
 use komorebi_core::DefaultLayout;
+use komorebi_core::Direction;
+use komorebi_core::DirectionStrategy;
 use komorebi_core::Layout;
+use komorebi_core::LayoutCondition;
+use komorebi_core::LayoutFinding;
+use komorebi_core::LayoutOptions;
 use komorebi_core::OperationDirection;
+use komorebi_core::Padding;
 use komorebi_core::Rect;
+use komorebi_core::ResizeRatio;
+use komorebi_core::SizeLimit;
 
 use crate::container::Container;
 use crate::ring::Ring;
+use crate::static_config::LayoutRules;
 use crate::static_config::WorkspaceConfig;
 use crate::window::Window;
 use crate::window::WindowDetails;
 use crate::windows_api::WindowsApi;
 use crate::DEFAULT_CONTAINER_PADDING;
 use crate::DEFAULT_WORKSPACE_PADDING;
+use crate::HIDDEN_HWNDS;
 use crate::INITIAL_CONFIGURATION_LOADED;
 use crate::NO_TITLEBAR;
 use crate::REMOVE_TITLEBARS;
@@ -51,18 +70,37 @@ pub struct Workspace {
     #[getset(get = "pub", get_mut = "pub", set = "pub")]
     layout: Layout,
     #[getset(get = "pub", get_mut = "pub", set = "pub")]
-    layout_rules: Vec<(usize, Layout)>,
+    layout_rules: Vec<(LayoutCondition, Layout)>,
     #[getset(get_copy = "pub", set = "pub")]
     layout_flip: Option<Axis>,
+    #[getset(get_copy = "pub", get_mut = "pub", set = "pub")]
+    layout_options: LayoutOptions,
+    /// Offset of the viewport from the start of the strip of columns in the scrolling layout
+    #[serde(skip_serializing)]
+    #[getset(get_copy = "pub", set = "pub")]
+    scroll_offset: i32,
+    #[getset(get_copy = "pub", set = "pub")]
+    direction_strategy: DirectionStrategy,
+    #[serde(skip_serializing)]
+    #[getset(get = "pub")]
+    focus_history: VecDeque<String>,
+    #[getset(get_copy = "pub", set = "pub")]
+    workspace_padding: Option<Padding>,
+    #[getset(get_copy = "pub", set = "pub")]
+    container_padding: Option<Padding>,
+    #[getset(get_copy = "pub", set = "pub")]
+    smart_gaps: bool,
     #[getset(get_copy = "pub", set = "pub")]
-    workspace_padding: Option<i32>,
+    max_width: Option<SizeLimit>,
     #[getset(get_copy = "pub", set = "pub")]
-    container_padding: Option<i32>,
+    max_height: Option<SizeLimit>,
     #[serde(skip_serializing)]
     #[getset(get = "pub", set = "pub")]
     latest_layout: Vec<Rect>,
     #[getset(get = "pub", get_mut = "pub", set = "pub")]
-    resize_dimensions: Vec<Option<Rect>>,
+    resize_dimensions: Vec<Option<ResizeRatio>>,
+    #[getset(get = "pub", set = "pub")]
+    container_constraints: Vec<Option<ContainerConstraints>>,
     #[getset(get = "pub", set = "pub")]
     tile: bool,
 }
@@ -82,10 +120,18 @@ impl Default for Workspace {
             layout: Layout::Default(DefaultLayout::BSP),
             layout_rules: vec![],
             layout_flip: None,
-            workspace_padding: Option::from(DEFAULT_WORKSPACE_PADDING.load(Ordering::SeqCst)),
-            container_padding: Option::from(DEFAULT_CONTAINER_PADDING.load(Ordering::SeqCst)),
+            layout_options: LayoutOptions::default(),
+            scroll_offset: 0,
+            direction_strategy: DirectionStrategy::default(),
+            focus_history: VecDeque::default(),
+            workspace_padding: Option::from(*DEFAULT_WORKSPACE_PADDING.lock()),
+            container_padding: Option::from(*DEFAULT_CONTAINER_PADDING.lock()),
+            smart_gaps: false,
+            max_width: None,
+            max_height: None,
             latest_layout: vec![],
             resize_dimensions: vec![],
+            container_constraints: vec![],
             tile: true,
         }
     }
@@ -103,14 +149,44 @@ impl Workspace {
             self.set_workspace_padding(config.workspace_padding);
         }
 
+        self.set_smart_gaps(config.smart_gaps.unwrap_or_default());
+
+        self.set_max_width(config.max_width);
+        self.set_max_height(config.max_height);
+
+        if let Some(main_ratio) = config.main_ratio {
+            self.layout_options = self.layout_options.with_main_ratio(main_ratio);
+        }
+
+        if let Some(column_ratio) = config.column_ratio {
+            self.layout_options = self.layout_options.with_column_ratio(column_ratio);
+        }
+
+        if let Some(master_count) = config.master_count {
+            self.layout_options = self.layout_options.with_master_count(master_count);
+        }
+
+        if let Some(bsp_strategy) = config.bsp_strategy {
+            self.layout_options.bsp_strategy = bsp_strategy;
+        }
+
+        if let Some(stack_placement) = config.stack_placement {
+            self.layout_options.stack_placement = stack_placement;
+        }
+
+        self.layout_options.single_container_width = config.single_container_width;
+
+        if let Some(direction_strategy) = config.direction_strategy {
+            self.direction_strategy = direction_strategy;
+        }
+
         if let Some(layout) = &config.layout {
             self.layout = Layout::Default(*layout);
             self.tile = true;
         }
 
         if let Some(pathbuf) = &config.custom_layout {
-            let layout = CustomLayout::from_path(pathbuf)?;
-            self.layout = Layout::Custom(layout);
+            self.layout = Layout::from_custom_path(pathbuf)?;
             self.tile = true;
         }
 
@@ -120,8 +196,25 @@ impl Workspace {
 
         if let Some(layout_rules) = &config.layout_rules {
             let mut all_rules = vec![];
-            for (count, rule) in layout_rules {
-                all_rules.push((*count, Layout::Default(*rule)));
+            match layout_rules {
+                LayoutRules::ContainerCount(layout_rules) => {
+                    for (count, rule) in layout_rules {
+                        all_rules.push((LayoutCondition::from(*count), Layout::Default(*rule)));
+                    }
+                }
+                LayoutRules::Conditional(layout_rules) => {
+                    for rule in layout_rules {
+                        let layout = match (rule.layout, &rule.custom_layout) {
+                            (Some(layout), None) => Layout::Default(layout),
+                            (None, Some(pathbuf)) => Layout::from_custom_path(pathbuf)?,
+                            _ => bail!(
+                                "each layout rule must have either a layout or a custom_layout"
+                            ),
+                        };
+
+                        all_rules.push((rule.condition.clone(), layout));
+                    }
+                }
             }
 
             self.set_layout_rules(all_rules);
@@ -130,11 +223,16 @@ impl Workspace {
         if let Some(layout_rules) = &config.custom_layout_rules {
             let rules = self.layout_rules_mut();
             for (count, pathbuf) in layout_rules {
-                let rule = CustomLayout::from_path(pathbuf)?;
-                rules.push((*count, Layout::Custom(rule)));
+                let rule = Layout::from_custom_path(pathbuf)?;
+                rules.push((LayoutCondition::from(*count), rule));
             }
         }
 
+        // Rules are checked in order of the number of containers they need, so that the rule for
+        // the most containers which are on the workspace wins
+        self.layout_rules_mut()
+            .sort_by_key(|rule| rule.0.containers);
+
         Ok(())
     }
 
@@ -198,17 +296,23 @@ impl Workspace {
         Ok(())
     }
 
-    pub fn update(
+    /// The layout rule which supplies the layout of this workspace, which is the last rule whose
+    /// conditions are met
+    pub fn active_layout_rule_mut(
         &mut self,
         work_area: &Rect,
-        offset: Option<Rect>,
-        invisible_borders: &Rect,
-    ) -> Result<()> {
-        if !INITIAL_CONFIGURATION_LOADED.load(Ordering::SeqCst) {
-            return Ok(());
-        }
+        monitor_identifiers: &[String],
+    ) -> Option<&mut (LayoutCondition, Layout)> {
+        let container_count = self.containers().len();
+
+        self.layout_rules_mut().iter_mut().rev().find(|rule| {
+            rule.0
+                .matches(container_count, work_area, monitor_identifiers)
+        })
+    }
 
-        let container_padding = self.container_padding();
+    /// The area that layouts are calculated in after applying the offset and workspace padding
+    pub fn layout_area(&self, work_area: &Rect, offset: Option<Rect>) -> Rect {
         let mut adjusted_work_area = offset.map_or_else(
             || *work_area,
             |offset| {
@@ -222,15 +326,65 @@ impl Workspace {
             },
         );
 
-        adjusted_work_area.add_padding(self.workspace_padding());
+        adjusted_work_area.add_padding(self.layout_workspace_padding());
+
+        adjusted_work_area
+    }
+
+    /// Whether padding is dropped because smart gaps are enabled and only one container is visible
+    fn has_smart_gaps(&self) -> bool {
+        self.smart_gaps() && (self.monocle_container().is_some() || self.containers().len() == 1)
+    }
+
+    /// The workspace padding used when laying out containers, taking smart gaps into account
+    pub fn layout_workspace_padding(&self) -> Option<Padding> {
+        if self.has_smart_gaps() {
+            None
+        } else {
+            self.workspace_padding()
+        }
+    }
+
+    /// The container padding used when laying out containers, taking smart gaps into account
+    pub fn layout_container_padding(&self) -> Option<Padding> {
+        if self.has_smart_gaps() {
+            None
+        } else {
+            self.container_padding()
+        }
+    }
+
+    pub fn update(
+        &mut self,
+        work_area: &Rect,
+        offset: Option<Rect>,
+        invisible_borders: &Rect,
+        monitor_identifiers: &[String],
+    ) -> Result<()> {
+        if !INITIAL_CONFIGURATION_LOADED.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+
+        let container_padding = self.layout_container_padding();
+        let mut adjusted_work_area = self.layout_area(work_area, offset);
+        let max_width = self
+            .max_width()
+            .map(|max_width| max_width.resolve(adjusted_work_area.right));
+        let max_height = self
+            .max_height()
+            .map(|max_height| max_height.resolve(adjusted_work_area.bottom));
 
         self.enforce_resize_constraints();
 
         if !self.layout_rules().is_empty() {
             let mut updated_layout = None;
 
+            // The last rule whose conditions are met by the current work area and monitor wins
             for rule in self.layout_rules() {
-                if self.containers().len() >= rule.0 {
+                if rule
+                    .0
+                    .matches(self.containers().len(), work_area, monitor_identifiers)
+                {
                     updated_layout = Option::from(rule.1.clone());
                 }
             }
@@ -248,23 +402,65 @@ impl Workspace {
             if let Some(container) = self.monocle_container_mut() {
                 if let Some(window) = container.focused_window_mut() {
                     adjusted_work_area.add_padding(container_padding);
+                    adjusted_work_area.constrain(max_width, max_height);
                     window.set_position(&adjusted_work_area, invisible_borders, true)?;
                 };
             } else if let Some(window) = self.maximized_window_mut() {
                 window.maximize();
             } else if !self.containers().is_empty() {
-                let layouts = self.layout().as_boxed_arrangement().calculate(
+                // The viewport of the scrolling layout follows the focused container
+                if matches!(self.layout, Layout::Default(DefaultLayout::Scrolling)) {
+                    self.scroll_offset = scroll_offset(
+                        &adjusted_work_area,
+                        self.focused_container_idx(),
+                        self.containers().len(),
+                        self.layout_options(),
+                        self.scroll_offset(),
+                        self.resize_dimensions(),
+                    );
+                }
+
+                // The constraints of each container come from the rules which match its focused
+                // window, so they follow the window when containers are moved or stacked
+                let mut container_constraints = vec![];
+                for container in self.containers() {
+                    container_constraints.push(match container.focused_window() {
+                        Some(window) => window.size_constraints(),
+                        None => None,
+                    });
+                }
+
+                self.set_container_constraints(container_constraints);
+
+                let mut layouts = self.layout().as_boxed_arrangement().calculate_constrained(
                     &adjusted_work_area,
                     NonZeroUsize::new(self.containers().len()).ok_or_else(|| {
                         anyhow!(
                             "there must be at least one container to calculate a workspace layout"
                         )
                     })?,
-                    self.container_padding(),
+                    container_padding,
                     self.layout_flip(),
+                    self.layout_options(),
+                    self.scroll_offset(),
                     self.resize_dimensions(),
+                    self.container_constraints(),
                 );
 
+                // Containers are capped and centered after the layout is calculated, so that
+                // a lone container isn't stretched across the whole of a wide monitor
+                for layout in &mut layouts {
+                    layout.constrain(max_width, max_height);
+                }
+
+                // Debug builds log any way in which the arrangement fails to tile the work area,
+                // such as the out of bounds containers that some sequences of resizes produce
+                if cfg!(debug_assertions) {
+                    for finding in self.check_layout(work_area, offset, &layouts) {
+                        tracing::warn!("layout check failed: {finding}");
+                    }
+                }
+
                 let should_remove_titlebars = REMOVE_TITLEBARS.load(Ordering::SeqCst);
                 let no_titlebar = NO_TITLEBAR.lock().clone();
 
@@ -277,6 +473,17 @@ impl Workspace {
                             window.add_title_bar()?;
                         }
 
+                        // Containers laid out entirely outside of the work area are hidden
+                        // until they are brought back into view
+                        if !layout.intersects(&adjusted_work_area) {
+                            window.hide();
+                            continue;
+                        }
+
+                        if HIDDEN_HWNDS.lock().contains(&window.hwnd) {
+                            window.restore();
+                        }
+
                         window.set_position(layout, invisible_borders, false)?;
                     }
                 }
@@ -294,6 +501,47 @@ impl Workspace {
         Ok(())
     }
 
+    /// Checks the layout applied to the tiled containers for overlaps, gaps, containers outside of
+    /// the work area and containers which are left without any space
+    ///
+    /// Workspaces which aren't showing their tiled containers, because they are not tiling or have
+    /// a monocle container or a maximized window, have nothing to check
+    pub fn check_layout(
+        &self,
+        work_area: &Rect,
+        offset: Option<Rect>,
+        layouts: &[Rect],
+    ) -> Vec<LayoutFinding> {
+        if !*self.tile() || self.monocle_container().is_some() || self.maximized_window().is_some()
+        {
+            return vec![];
+        }
+
+        NonZeroUsize::new(self.containers().len()).map_or_else(Vec::new, |len| {
+            // Containers capped to a maximum size and windows letterboxed to an aspect ratio
+            // leave gaps in the work area by design
+            let covers_area = self
+                .layout()
+                .as_boxed_arrangement()
+                .covers_area(len, self.layout_options())
+                && self.max_width().is_none()
+                && self.max_height().is_none()
+                && !self
+                    .container_constraints()
+                    .iter()
+                    .flatten()
+                    .any(|constraints| constraints.aspect_ratio.is_some());
+
+            check_layouts(
+                &self.layout_area(work_area, offset),
+                len,
+                self.layout_container_padding(),
+                layouts,
+                covers_area,
+            )
+        })
+    }
+
     pub fn reap_orphans(&mut self) -> Result<(usize, usize)> {
         let mut hwnds = vec![];
         let mut floating_hwnds = vec![];
@@ -480,7 +728,7 @@ impl Workspace {
             .ok_or_else(|| anyhow!("there is no container"))?;
 
         let primary_idx = match self.layout() {
-            Layout::Default(_) => 0,
+            Layout::Default(_) | Layout::Tree(_) => 0,
             Layout::Custom(layout) => layout.first_container_idx(
                 layout
                     .primary_idx()
@@ -517,7 +765,7 @@ impl Workspace {
         None
     }
 
-    fn container_idx_for_window(&self, hwnd: isize) -> Option<usize> {
+    pub fn container_idx_for_window(&self, hwnd: isize) -> Option<usize> {
         let mut idx = None;
         for (i, x) in self.containers().iter().enumerate() {
             if x.contains_window(hwnd) {
@@ -617,9 +865,34 @@ impl Workspace {
     pub fn new_idx_for_direction(&self, direction: OperationDirection) -> Option<usize> {
         let len = NonZeroUsize::new(self.containers().len())?;
 
+        if matches!(self.direction_strategy(), DirectionStrategy::Geometric)
+            && self.latest_layout().len() == len.get()
+        {
+            let focus_history = self
+                .focus_history()
+                .iter()
+                .filter_map(|id| self.containers().iter().position(|c| c.id() == id))
+                .collect::<Vec<_>>();
+
+            let geometric = Geometric {
+                layouts: self.latest_layout(),
+                focus_history: &focus_history,
+            };
+
+            // The calculated layouts have already been flipped, so there is no flip to undo here
+            return direction.destination(
+                &geometric as &dyn Direction,
+                None,
+                self.layout_options(),
+                self.focused_container_idx(),
+                len,
+            );
+        }
+
         direction.destination(
             self.layout().as_boxed_direction().as_ref(),
             self.layout_flip(),
+            self.layout_options(),
             self.focused_container_idx(),
             len,
         )
@@ -787,88 +1060,351 @@ impl Workspace {
             Layout::Default(DefaultLayout::UltrawideVerticalStack) => {
                 self.enforce_resize_for_ultrawide();
             }
-            _ => self.enforce_no_resize(),
+            Layout::Default(DefaultLayout::Columns) => self.enforce_resize_for_columns(),
+            Layout::Default(DefaultLayout::Rows) => self.enforce_resize_for_rows(),
+            Layout::Default(DefaultLayout::VerticalStack) => {
+                self.enforce_resize_for_vertical_stack();
+            }
+            Layout::Default(DefaultLayout::HorizontalStack) => {
+                self.enforce_resize_for_horizontal_stack();
+            }
+            Layout::Default(DefaultLayout::Grid) => self.enforce_resize_for_grid(),
+            Layout::Default(DefaultLayout::Scrolling) => self.enforce_resize_for_scrolling(),
+            Layout::Default(DefaultLayout::CenteredMaster) => {
+                self.enforce_resize_for_centered_master();
+            }
+            Layout::Custom(ref layout) => {
+                let columns = layout.column_containers(self.resize_dimensions().len());
+                let rows = matches!(layout.orientation(), Orientation::Rows);
+                self.enforce_resize_for_custom(&columns, rows);
+            }
+            // Containers in a tree of splits are always sized by the ratios of the tree
+            Layout::Tree(_) => self.enforce_no_resize(),
+        }
+    }
+
+    fn enforce_resize_for_columns(&mut self) {
+        let resize_dimensions = self.resize_dimensions_mut();
+        Self::enforce_resize_for_run(resize_dimensions, Axis::Horizontal);
+
+        // Columns always span the full height of the work area
+        for rect in resize_dimensions.iter_mut().flatten() {
+            rect.top = 0.0;
+            rect.bottom = 0.0;
+        }
+    }
+
+    fn enforce_resize_for_rows(&mut self) {
+        let resize_dimensions = self.resize_dimensions_mut();
+        Self::enforce_resize_for_run(resize_dimensions, Axis::Vertical);
+
+        // Rows always span the full width of the work area
+        for rect in resize_dimensions.iter_mut().flatten() {
+            rect.left = 0.0;
+            rect.right = 0.0;
+        }
+    }
+
+    fn enforce_resize_for_scrolling(&mut self) {
+        // Columns on the strip always span the full height of the work area, but can be made
+        // wider or narrower from either edge
+        for rect in self.resize_dimensions_mut().iter_mut().flatten() {
+            rect.top = 0.0;
+            rect.bottom = 0.0;
+        }
+    }
+
+    fn enforce_resize_for_centered_master(&mut self) {
+        // Stack containers alternate between sides, so the edges which can't be resized are found
+        // from where the containers sit in an unresized layout; a lone container always fills
+        // the area here, as it has no neighbours to resize against
+        let area = Rect {
+            left: 0,
+            top: 0,
+            right: 1 << 16,
+            bottom: 1 << 16,
+        };
+
+        let layout_options = LayoutOptions {
+            single_container_width: None,
+            ..self.layout_options()
+        };
+
+        let layouts = match NonZeroUsize::new(self.resize_dimensions().len()) {
+            Some(len) => self.layout().as_boxed_arrangement().calculate(
+                &area,
+                len,
+                None,
+                None,
+                layout_options,
+                0,
+                &[],
+            ),
+            None => return,
+        };
+
+        for (rect, layout) in self.resize_dimensions_mut().iter_mut().zip(layouts) {
+            if let Some(rect) = rect {
+                if layout.left == area.left {
+                    rect.left = 0.0;
+                }
+
+                if layout.top == area.top {
+                    rect.top = 0.0;
+                }
+
+                if layout.left + layout.right == area.right {
+                    rect.right = 0.0;
+                }
+
+                if layout.top + layout.bottom == area.bottom {
+                    rect.bottom = 0.0;
+                }
+            }
+        }
+    }
+
+    fn enforce_resize_for_vertical_stack(&mut self) {
+        let masters = self
+            .layout_options()
+            .masters(self.resize_dimensions().len());
+
+        let resize_dimensions = self.resize_dimensions_mut();
+        let has_stack = resize_dimensions.len() > masters;
+        let (main, stack) = resize_dimensions.split_at_mut(masters);
+
+        // The main area and the stack are each a run of rows which can be resized up and down
+        // within themselves, and only the edge between the two can be resized horizontally
+        Self::enforce_resize_for_run(main, Axis::Vertical);
+        Self::enforce_resize_for_run(stack, Axis::Vertical);
+
+        for rect in main.iter_mut().flatten() {
+            rect.left = 0.0;
+
+            if !has_stack {
+                rect.right = 0.0;
+            }
+        }
+
+        for rect in stack.iter_mut().flatten() {
+            rect.right = 0.0;
+        }
+    }
+
+    fn enforce_resize_for_horizontal_stack(&mut self) {
+        let masters = self
+            .layout_options()
+            .masters(self.resize_dimensions().len());
+
+        let resize_dimensions = self.resize_dimensions_mut();
+        let has_stack = resize_dimensions.len() > masters;
+        let (main, stack) = resize_dimensions.split_at_mut(masters);
+
+        // The main area and the stack are each a run of columns which can be resized left and
+        // right within themselves, and only the edge between the two can be resized vertically
+        Self::enforce_resize_for_run(main, Axis::Horizontal);
+        Self::enforce_resize_for_run(stack, Axis::Horizontal);
+
+        for rect in main.iter_mut().flatten() {
+            rect.top = 0.0;
+
+            if !has_stack {
+                rect.bottom = 0.0;
+            }
+        }
+
+        for rect in stack.iter_mut().flatten() {
+            rect.bottom = 0.0;
+        }
+    }
+
+    fn enforce_resize_for_grid(&mut self) {
+        let (columns, rows) = grid_dimensions(self.resize_dimensions().len());
+
+        for (row, cells) in self.resize_dimensions_mut().chunks_mut(columns).enumerate() {
+            Self::enforce_resize_for_run(cells, Axis::Horizontal);
+
+            for rect in cells.iter_mut().flatten() {
+                // The first row can never be resized to the top
+                if row == 0 {
+                    rect.top = 0.0;
+                }
+
+                // The last row can never be resized to the bottom
+                if row == rows - 1 {
+                    rect.bottom = 0.0;
+                }
+            }
+        }
+    }
+
+    fn enforce_resize_for_custom(&mut self, columns: &[(Range<usize>, ColumnSplit)], rows: bool) {
+        let last_column = columns.len().saturating_sub(1);
+        let resize_dimensions = self.resize_dimensions_mut();
+
+        // Row-oriented layouts are constrained as the columns of their transposed layout
+        let transpose = |resize_dimensions: &mut [Option<ResizeRatio>]| {
+            if rows {
+                for rect in resize_dimensions.iter_mut().flatten() {
+                    *rect = rect.transposed();
+                }
+            }
+        };
+
+        transpose(resize_dimensions);
+
+        for (column, (containers, split)) in columns.iter().enumerate() {
+            let run = &mut resize_dimensions[containers.clone()];
+            let len = run.len();
+
+            for (i, rect) in run.iter_mut().enumerate() {
+                if let Some(rect) = rect {
+                    // Only containers touching the outer edges of the layout are constrained
+                    // horizontally; the edges between columns can always be moved
+                    let (touches_left, touches_right) = match split {
+                        ColumnSplit::Horizontal => (true, true),
+                        ColumnSplit::Vertical => (i == 0, i == len - 1),
+                    };
+
+                    if column == 0 && touches_left {
+                        rect.left = 0.0;
+                    }
+
+                    if column == last_column && touches_right {
+                        rect.right = 0.0;
+                    }
+
+                    match split {
+                        ColumnSplit::Horizontal => {
+                            if i == 0 {
+                                rect.top = 0.0;
+                            }
+
+                            if i == len - 1 {
+                                rect.bottom = 0.0;
+                            }
+                        }
+                        // Vertically split containers always span the full height of the column
+                        ColumnSplit::Vertical => {
+                            rect.top = 0.0;
+                            rect.bottom = 0.0;
+                        }
+                    }
+                }
+            }
+        }
+
+        transpose(resize_dimensions);
+    }
+
+    // Containers in a run are laid out next to each other along the given axis, and
+    // the first and last containers of a run can't be resized past the ends of the run
+    fn enforce_resize_for_run(run: &mut [Option<ResizeRatio>], axis: Axis) {
+        let len = run.len();
+
+        for (i, rect) in run.iter_mut().enumerate() {
+            if let Some(rect) = rect {
+                match axis {
+                    Axis::Horizontal | Axis::HorizontalAndVertical => {
+                        if i == 0 {
+                            rect.left = 0.0;
+                        }
+
+                        if i == len - 1 {
+                            rect.right = 0.0;
+                        }
+                    }
+                    Axis::Vertical => {
+                        if i == 0 {
+                            rect.top = 0.0;
+                        }
+
+                        if i == len - 1 {
+                            rect.bottom = 0.0;
+                        }
+                    }
+                }
+            }
         }
     }
 
     fn enforce_resize_constraints_for_bsp(&mut self) {
+        // Only dwindle has edges which can be determined by index; the other strategies
+        // ignore adjustments to edges that don't border another container when arranging
+        if !matches!(self.layout_options().bsp_strategy, BspStrategy::Dwindle) {
+            return;
+        }
+
         for (i, rect) in self.resize_dimensions_mut().iter_mut().enumerate() {
             if let Some(rect) = rect {
                 // Even containers can't be resized to the bottom
                 if i % 2 == 0 {
-                    rect.bottom = 0;
+                    rect.bottom = 0.0;
                     // Odd containers can't be resized to the right
                 } else {
-                    rect.right = 0;
+                    rect.right = 0.0;
                 }
             }
         }
 
         // The first container can never be resized to the left or the top
         if let Some(Some(first)) = self.resize_dimensions_mut().first_mut() {
-            first.top = 0;
-            first.left = 0;
+            first.top = 0.0;
+            first.left = 0.0;
         }
 
         // The last container can never be resized to the bottom or the right
         if let Some(Some(last)) = self.resize_dimensions_mut().last_mut() {
-            last.bottom = 0;
-            last.right = 0;
+            last.bottom = 0.0;
+            last.right = 0.0;
         }
     }
 
     fn enforce_resize_for_ultrawide(&mut self) {
+        let masters = self
+            .layout_options()
+            .masters(self.resize_dimensions().len());
+
+        // The masters alone fill the whole area, so they can't be resized at all
+        if self.resize_dimensions().len() <= masters {
+            self.enforce_no_resize();
+            return;
+        }
+
         let resize_dimensions = self.resize_dimensions_mut();
-        match resize_dimensions.len() {
-            // Single window can not be resized at all
-            0 | 1 => self.enforce_no_resize(),
-            // Two windows can only be resized in the middle
-            2 => {
-                // Zero is actually on the right
-                if let Some(mut right) = resize_dimensions[0] {
-                    right.top = 0;
-                    right.bottom = 0;
-                    right.right = 0;
-                }
+        let (main, others) = resize_dimensions.split_at_mut(masters);
+        let (secondary, stack) = others.split_at_mut(1);
 
-                // One is on the left
-                if let Some(mut left) = resize_dimensions[1] {
-                    left.top = 0;
-                    left.bottom = 0;
-                    left.left = 0;
-                }
+        // The masters are split evenly into rows within the primary column, which is in the center
+        // with a stack on the right, or otherwise on the right of the secondary column
+        for rect in main.iter_mut().flatten() {
+            rect.top = 0.0;
+            rect.bottom = 0.0;
+
+            if stack.is_empty() {
+                rect.right = 0.0;
             }
-            // Three or more windows means 0 is in center, 1 is at the left, 2.. are a vertical
-            // stack on the right
-            _ => {
-                // Central can be resized left or right
-                if let Some(mut right) = resize_dimensions[0] {
-                    right.top = 0;
-                    right.bottom = 0;
-                }
+        }
 
-                // Left one can only be resized to the right
-                if let Some(mut left) = resize_dimensions[1] {
-                    left.top = 0;
-                    left.bottom = 0;
-                    left.left = 0;
-                }
+        // The secondary column on the left can only be resized to the right
+        if let Some(Some(left)) = secondary.first_mut() {
+            left.top = 0.0;
+            left.bottom = 0.0;
+            left.left = 0.0;
+        }
 
-                // Handle stack on the right
-                let stack_size = resize_dimensions[2..].len();
-                for (i, rect) in resize_dimensions[2..].iter_mut().enumerate() {
-                    if let Some(rect) = rect {
-                        // No containers can resize to the right
-                        rect.right = 0;
+        // The stack on the right can't be resized to the right, or past the top and the bottom
+        let stack_size = stack.len();
+        for (i, rect) in stack.iter_mut().enumerate() {
+            if let Some(rect) = rect {
+                rect.right = 0.0;
 
-                        // First container in stack cant resize up
-                        if i == 0 {
-                            rect.top = 0;
-                        } else if i == stack_size - 1 {
-                            // Last cant be resized to the bottom
-                            rect.bottom = 0;
-                        }
-                    }
+                if i == 0 {
+                    rect.top = 0.0;
+                }
+
+                if i == stack_size - 1 {
+                    rect.bottom = 0.0;
                 }
             }
         }
@@ -876,10 +1412,10 @@ impl Workspace {
 
     fn enforce_no_resize(&mut self) {
         for rect in self.resize_dimensions_mut().iter_mut().flatten() {
-            rect.left = 0;
-            rect.right = 0;
-            rect.top = 0;
-            rect.bottom = 0;
+            rect.left = 0.0;
+            rect.right = 0.0;
+            rect.top = 0.0;
+            rect.bottom = 0.0;
         }
     }
 
@@ -1050,6 +1586,17 @@ impl Workspace {
         tracing::info!("focusing container");
 
         self.containers.focus(idx);
+
+        if let Some(id) = self.containers().get(idx).map(|c| c.id().clone()) {
+            let ids = self
+                .containers()
+                .iter()
+                .map(|c| c.id().clone())
+                .collect::<Vec<_>>();
+
+            self.focus_history.retain(|h| *h != id && ids.contains(h));
+            self.focus_history.push_front(id);
+        }
     }
 
     pub fn swap_containers(&mut self, i: usize, j: usize) {
@@ -7,6 +7,7 @@ use std::io::BufRead;
 use std::io::BufReader;
 use std::io::ErrorKind;
 use std::io::Write;
+use std::num::NonZeroUsize;
 use std::path::Path;
 use std::path::PathBuf;
 use std::process::Command;
@@ -39,16 +40,26 @@ use windows::Win32::UI::WindowsAndMessaging::SW_RESTORE;
 use derive_ahk::AhkFunction;
 use derive_ahk::AhkLibrary;
 use komorebi_core::config_generation::ApplicationConfigurationGenerator;
+use komorebi_core::resize_ratio::SavedResizeDimensions;
 use komorebi_core::ApplicationIdentifier;
 use komorebi_core::Axis;
+use komorebi_core::BalanceScope;
+use komorebi_core::BspStrategy;
 use komorebi_core::CycleDirection;
 use komorebi_core::DefaultLayout;
+use komorebi_core::DirectionStrategy;
 use komorebi_core::FocusFollowsMouseImplementation;
 use komorebi_core::HidingBehaviour;
+use komorebi_core::Layout;
+use komorebi_core::LayoutOptions;
+use komorebi_core::LayoutPreview;
 use komorebi_core::MoveBehaviour;
 use komorebi_core::OperationBehaviour;
 use komorebi_core::OperationDirection;
+use komorebi_core::Padding;
+use komorebi_core::PreviewFormat;
 use komorebi_core::Rect;
+use komorebi_core::SizeLimit;
 use komorebi_core::Sizing;
 use komorebi_core::SocketMessage;
 use komorebi_core::StateQuery;
@@ -160,6 +171,7 @@ gen_enum_subcommand_args! {
     CycleWorkspace: CycleDirection,
     Stack: OperationDirection,
     CycleStack: CycleDirection,
+    CycleScratchpad: CycleDirection,
     FlipLayout: Axis,
     ChangeLayout: DefaultLayout,
     CycleLayout: CycleDirection,
@@ -248,6 +260,8 @@ gen_workspace_subcommand_args! {
     Name: String,
     Layout: #[enum] DefaultLayout,
     Tiling: #[enum] BooleanState,
+    DirectionStrategy: #[enum] DirectionStrategy,
+    BspStrategy: #[enum] BspStrategy,
 }
 
 macro_rules! gen_named_workspace_subcommand_args {
@@ -276,6 +290,8 @@ macro_rules! gen_named_workspace_subcommand_args {
 gen_named_workspace_subcommand_args! {
     Layout: #[enum] DefaultLayout,
     Tiling: #[enum] BooleanState,
+    DirectionStrategy: #[enum] DirectionStrategy,
+    BspStrategy: #[enum] BspStrategy,
 }
 
 #[derive(Parser, AhkFunction)]
@@ -308,6 +324,37 @@ pub struct NamedWorkspaceCustomLayout {
     path: PathBuf,
 }
 
+#[derive(Parser, AhkFunction)]
+pub struct WorkspaceMaxSize {
+    /// Monitor index (zero-indexed)
+    monitor: usize,
+
+    /// Workspace index on the specified monitor (zero-indexed)
+    workspace: usize,
+
+    /// Maximum width of each container, in pixels (e.g. 1920) or as a percentage of the work area (e.g. 60%)
+    #[clap(long)]
+    width: Option<SizeLimit>,
+
+    /// Maximum height of each container, in pixels (e.g. 1080) or as a percentage of the work area (e.g. 80%)
+    #[clap(long)]
+    height: Option<SizeLimit>,
+}
+
+#[derive(Parser, AhkFunction)]
+pub struct NamedWorkspaceMaxSize {
+    /// Target workspace name
+    workspace: String,
+
+    /// Maximum width of each container, in pixels (e.g. 1920) or as a percentage of the work area (e.g. 60%)
+    #[clap(long)]
+    width: Option<SizeLimit>,
+
+    /// Maximum height of each container, in pixels (e.g. 1080) or as a percentage of the work area (e.g. 80%)
+    #[clap(long)]
+    height: Option<SizeLimit>,
+}
+
 #[derive(Parser, AhkFunction)]
 pub struct WorkspaceLayoutRule {
     /// Monitor index (zero-indexed)
@@ -378,6 +425,12 @@ struct ResizeAxis {
     sizing: Sizing,
 }
 
+#[derive(Parser, AhkFunction)]
+struct Balance {
+    #[clap(value_enum, default_value = "workspace")]
+    scope: BalanceScope,
+}
+
 #[derive(Parser, AhkFunction)]
 struct ResizeDelta {
     /// The delta of pixels by which to increase or decrease window dimensions when resizing
@@ -482,8 +535,8 @@ macro_rules! gen_focused_workspace_padding_subcommand_args {
         $(
             #[derive(clap::Parser, derive_ahk::AhkFunction)]
             pub struct $name {
-                /// Pixels size to set as an integer
-                size: i32,
+                /// Pixels size to set as an integer, or as top,right,bottom,left (e.g. 40,10,10,10)
+                size: Padding,
             }
         )+
     };
@@ -504,8 +557,8 @@ macro_rules! gen_padding_subcommand_args {
                 monitor: usize,
                 /// Workspace index on the specified monitor (zero-indexed)
                 workspace: usize,
-                /// Pixels to pad with as an integer
-                size: i32,
+                /// Pixels to pad with as an integer, or as top,right,bottom,left (e.g. 40,10,10,10)
+                size: Padding,
             }
         )+
     };
@@ -525,8 +578,8 @@ macro_rules! gen_named_padding_subcommand_args {
                 /// Target workspace name
                 workspace: String,
 
-                /// Pixels to pad with as an integer
-                size: i32,
+                /// Pixels to pad with as an integer, or as top,right,bottom,left (e.g. 40,10,10,10)
+                size: Padding,
             }
         )+
     };
@@ -545,7 +598,7 @@ macro_rules! gen_padding_adjustment_subcommand_args {
             pub struct $name {
                 #[clap(value_enum)]
                 sizing: Sizing,
-                /// Pixels to adjust by as an integer
+                /// Pixels to adjust every side by as an integer
                 adjustment: i32,
             }
         )+
@@ -557,6 +610,30 @@ gen_padding_adjustment_subcommand_args! {
     AdjustWorkspacePadding,
 }
 
+#[derive(Parser, AhkFunction)]
+struct AdjustMainRatio {
+    #[clap(value_enum)]
+    sizing: Sizing,
+    /// Ratio to adjust by as a decimal (e.g. 0.05)
+    adjustment: f32,
+}
+
+#[derive(Parser, AhkFunction)]
+struct AdjustMasterCount {
+    #[clap(value_enum)]
+    sizing: Sizing,
+    /// Number of containers to adjust by as an integer
+    adjustment: usize,
+}
+
+#[derive(Parser, AhkFunction)]
+struct AdjustColumnRatio {
+    #[clap(value_enum)]
+    sizing: Sizing,
+    /// Ratio to adjust by as a decimal (e.g. 0.05)
+    adjustment: f32,
+}
+
 macro_rules! gen_application_target_subcommand_args {
     // SubCommand Pattern
     ( $( $name:ident ),+ $(,)? ) => {
@@ -575,6 +652,7 @@ macro_rules! gen_application_target_subcommand_args {
 gen_application_target_subcommand_args! {
     FloatRule,
     ManageRule,
+    ScratchpadRule,
     IdentifyTrayApplication,
     IdentifyLayeredApplication,
     IdentifyObjectNameChangeApplication,
@@ -685,6 +763,9 @@ struct Start {
     /// Start a TCP server on the given port to allow the direct sending of SocketMessages
     #[clap(short, long)]
     tcp_port: Option<usize>,
+    /// Ignore the window manager state persisted by the last komorebi process
+    #[clap(long)]
+    clean_state: bool,
     /// Start whkd in a background process
     #[clap(long)]
     whkd: bool,
@@ -712,12 +793,100 @@ struct LoadResize {
     path: PathBuf,
 }
 
+#[derive(Parser, AhkFunction)]
+struct SaveSnapshot {
+    /// Name under which the snapshot should be saved
+    name: String,
+}
+
+#[derive(Parser, AhkFunction)]
+struct LoadSnapshot {
+    /// Name of the snapshot which should be restored
+    name: String,
+}
+
+#[derive(Parser, AhkFunction)]
+struct SendToScratchpad {
+    /// Name to toggle the window by (default: the executable of the window)
+    name: Option<String>,
+}
+
+#[derive(Parser, AhkFunction)]
+struct ToggleScratchpad {
+    /// Name of the scratchpad window which should be shown or hidden
+    name: String,
+}
+
+#[derive(Parser, AhkFunction)]
+struct Mark {
+    /// Label to attach to the focused window, replacing any window which already has it
+    mark: String,
+}
+
+#[derive(Parser, AhkFunction)]
+struct FocusMark {
+    /// Label of the window which should be focused
+    mark: String,
+}
+
+#[derive(Parser, AhkFunction)]
+struct SwapWithMark {
+    /// Label of the window whose container should be swapped with the focused container
+    mark: String,
+}
+
+#[derive(Parser, AhkFunction)]
+struct Unmark {
+    /// Label which should be removed
+    mark: String,
+}
+
 #[derive(Parser, AhkFunction)]
 struct LoadCustomLayout {
     /// JSON or YAML file from which the custom layout definition should be loaded
     path: PathBuf,
 }
 
+#[derive(Parser, AhkFunction)]
+struct ValidateLayout {
+    /// JSON or YAML file containing the custom layout definition to check
+    path: PathBuf,
+}
+
+#[derive(Parser, AhkFunction)]
+struct PreviewLayout {
+    /// Layout to preview (default: bsp)
+    #[clap(value_enum, long, conflicts_with = "custom_layout")]
+    layout: Option<DefaultLayout>,
+    /// JSON or YAML file from which a custom layout definition should be loaded instead
+    #[clap(long)]
+    custom_layout: Option<PathBuf>,
+    /// Number of containers to lay out
+    #[clap(long, default_value = "3")]
+    containers: NonZeroUsize,
+    /// Width of the area to lay out in pixels
+    #[clap(long, default_value = "1920")]
+    width: i32,
+    /// Height of the area to lay out in pixels
+    #[clap(long, default_value = "1080")]
+    height: i32,
+    /// Pixels to pad containers with as an integer, or as top,right,bottom,left (e.g. 40,10,10,10)
+    #[clap(long)]
+    padding: Option<Padding>,
+    /// Axis on which to flip the layout
+    #[clap(value_enum, long)]
+    flip: Option<Axis>,
+    /// File from which resize layout dimensions saved with 'save-resize' should be loaded
+    #[clap(long)]
+    resize: Option<PathBuf>,
+    /// Format of the preview (default: ascii)
+    #[clap(value_enum, long)]
+    format: Option<PreviewFormat>,
+    /// File to write the preview to instead of printing it
+    #[clap(long)]
+    output: Option<PathBuf>,
+}
+
 #[derive(Parser, AhkFunction)]
 struct Subscribe {
     /// Name of the pipe to send event notifications to (without "\\.\pipe\" prepended)
@@ -795,6 +964,8 @@ enum SubCommand {
     State,
     /// Show a JSON representation of visible windows
     VisibleWindows,
+    /// Show any overlaps, gaps or out of bounds containers in the layout of each focused workspace
+    CheckLayout,
     /// Query the current window manager state
     #[clap(arg_required_else_help = true)]
     Query(Query),
@@ -820,6 +991,14 @@ enum SubCommand {
     #[clap(arg_required_else_help = true)]
     #[clap(alias = "load")]
     LoadResize(LoadResize),
+    /// Save the layout and windows of every workspace as a named snapshot
+    #[clap(arg_required_else_help = true)]
+    SaveSnapshot(SaveSnapshot),
+    /// Restore a named snapshot by moving the windows that are currently open into its containers
+    #[clap(arg_required_else_help = true)]
+    LoadSnapshot(LoadSnapshot),
+    /// List the names of the saved snapshots
+    ListSnapshots,
     /// Change focus to the window in the specified direction
     #[clap(arg_required_else_help = true)]
     Focus(Focus),
@@ -848,6 +1027,12 @@ enum SubCommand {
     /// Resize the focused window or primary column along the specified axis
     #[clap(arg_required_else_help = true)]
     ResizeAxis(ResizeAxis),
+    /// Reset the resize adjustments of the focused workspace, or only those in the given scope
+    Balance(Balance),
+    /// Undo the last change to the arrangement of windows, containers and workspaces
+    Undo,
+    /// Redo the last change to the arrangement which was undone
+    Redo,
     /// Unstack the focused window
     Unstack,
     /// Cycle the focused stack in the specified cycle direction
@@ -941,6 +1126,15 @@ enum SubCommand {
     /// Adjust workspace padding on the focused workspace
     #[clap(arg_required_else_help = true)]
     AdjustWorkspacePadding(AdjustWorkspacePadding),
+    /// Adjust the main area ratio of stack layouts on the focused workspace
+    #[clap(arg_required_else_help = true)]
+    AdjustMainRatio(AdjustMainRatio),
+    /// Adjust the number of containers in the main area of stack layouts on the focused workspace
+    #[clap(arg_required_else_help = true)]
+    AdjustMasterCount(AdjustMasterCount),
+    /// Adjust the width of the columns of the scrolling layout on the focused workspace
+    #[clap(arg_required_else_help = true)]
+    AdjustColumnRatio(AdjustColumnRatio),
     /// Set the layout on the focused workspace
     #[clap(arg_required_else_help = true)]
     ChangeLayout(ChangeLayout),
@@ -950,6 +1144,11 @@ enum SubCommand {
     /// Load a custom layout from file for the focused workspace
     #[clap(arg_required_else_help = true)]
     LoadCustomLayout(LoadCustomLayout),
+    /// Preview a layout or a custom layout file without a running komorebi instance
+    PreviewLayout(PreviewLayout),
+    /// Check a custom layout file and print every problem found in it
+    #[clap(arg_required_else_help = true)]
+    ValidateLayout(ValidateLayout),
     /// Flip the layout on the focused workspace (BSP only)
     #[clap(arg_required_else_help = true)]
     FlipLayout(FlipLayout),
@@ -1019,6 +1218,24 @@ enum SubCommand {
     /// Enable or disable window tiling for the specified workspace
     #[clap(arg_required_else_help = true)]
     NamedWorkspaceTiling(NamedWorkspaceTiling),
+    /// Set the strategy used to find the container in a given direction for the specified workspace
+    #[clap(arg_required_else_help = true)]
+    WorkspaceDirectionStrategy(WorkspaceDirectionStrategy),
+    /// Set the strategy used to find the container in a given direction for the specified workspace
+    #[clap(arg_required_else_help = true)]
+    NamedWorkspaceDirectionStrategy(NamedWorkspaceDirectionStrategy),
+    /// Set the strategy used to split containers in the BSP layout for the specified workspace
+    #[clap(arg_required_else_help = true)]
+    WorkspaceBspStrategy(WorkspaceBspStrategy),
+    /// Set the strategy used to split containers in the BSP layout for the specified workspace
+    #[clap(arg_required_else_help = true)]
+    NamedWorkspaceBspStrategy(NamedWorkspaceBspStrategy),
+    /// Set the maximum size of containers on the specified workspace, or remove it if no size is given
+    #[clap(arg_required_else_help = true)]
+    WorkspaceMaxSize(WorkspaceMaxSize),
+    /// Set the maximum size of containers on the specified workspace, or remove it if no size is given
+    #[clap(arg_required_else_help = true)]
+    NamedWorkspaceMaxSize(NamedWorkspaceMaxSize),
     /// Set the workspace name for the specified workspace
     #[clap(arg_required_else_help = true)]
     WorkspaceName(WorkspaceName),
@@ -1034,6 +1251,26 @@ enum SubCommand {
     ToggleMonocle,
     /// Toggle native maximization for the focused window
     ToggleMaximize,
+    /// Send the focused window to the scratchpad, or hide it if it is a scratchpad window
+    SendToScratchpad(SendToScratchpad),
+    /// Show the named scratchpad window floating on the focused monitor, or hide it if it is shown
+    #[clap(arg_required_else_help = true)]
+    ToggleScratchpad(ToggleScratchpad),
+    /// Show the next or previous scratchpad window in place of the one which is shown
+    #[clap(arg_required_else_help = true)]
+    CycleScratchpad(CycleScratchpad),
+    /// Attach a label to the focused window so that it can be focused or swapped with later
+    #[clap(arg_required_else_help = true)]
+    Mark(Mark),
+    /// Focus the window with a label, switching to its monitor and workspace
+    #[clap(arg_required_else_help = true)]
+    FocusMark(FocusMark),
+    /// Swap the focused container with the container of the window with a label
+    #[clap(arg_required_else_help = true)]
+    SwapWithMark(SwapWithMark),
+    /// Remove a label from the window that it is attached to
+    #[clap(arg_required_else_help = true)]
+    Unmark(Unmark),
     /// Restore all hidden windows (debugging command)
     RestoreWindows,
     /// Force komorebi to manage the focused window
@@ -1067,6 +1304,9 @@ enum SubCommand {
     /// Add a rule to always manage the specified application
     #[clap(arg_required_else_help = true)]
     ManageRule(ManageRule),
+    /// Add a rule to always send the specified application to the scratchpad
+    #[clap(arg_required_else_help = true)]
+    ScratchpadRule(ScratchpadRule),
     /// Add a rule to associate an application with a workspace on first show
     #[clap(arg_required_else_help = true)]
     InitialWorkspaceRule(InitialWorkspaceRule),
@@ -1450,6 +1690,15 @@ fn main() -> Result<()> {
         SubCommand::Retile => {
             send_message(&SocketMessage::Retile.as_bytes()?)?;
         }
+        SubCommand::Balance(arg) => {
+            send_message(&SocketMessage::Balance(arg.scope).as_bytes()?)?;
+        }
+        SubCommand::Undo => {
+            send_message(&SocketMessage::Undo.as_bytes()?)?;
+        }
+        SubCommand::Redo => {
+            send_message(&SocketMessage::Redo.as_bytes()?)?;
+        }
         SubCommand::Move(arg) => {
             send_message(&SocketMessage::MoveWindow(arg.operation_direction).as_bytes()?)?;
         }
@@ -1582,6 +1831,19 @@ fn main() -> Result<()> {
                 &SocketMessage::AdjustWorkspacePadding(arg.sizing, arg.adjustment).as_bytes()?,
             )?;
         }
+        SubCommand::AdjustMainRatio(arg) => {
+            send_message(&SocketMessage::AdjustMainRatio(arg.sizing, arg.adjustment).as_bytes()?)?;
+        }
+        SubCommand::AdjustMasterCount(arg) => {
+            send_message(
+                &SocketMessage::AdjustMasterCount(arg.sizing, arg.adjustment).as_bytes()?,
+            )?;
+        }
+        SubCommand::AdjustColumnRatio(arg) => {
+            send_message(
+                &SocketMessage::AdjustColumnRatio(arg.sizing, arg.adjustment).as_bytes()?,
+            )?;
+        }
         SubCommand::AdjustContainerPadding(arg) => {
             send_message(
                 &SocketMessage::AdjustContainerPadding(arg.sizing, arg.adjustment).as_bytes()?,
@@ -1602,6 +1864,27 @@ fn main() -> Result<()> {
         SubCommand::ToggleMaximize => {
             send_message(&SocketMessage::ToggleMaximize.as_bytes()?)?;
         }
+        SubCommand::SendToScratchpad(arg) => {
+            send_message(&SocketMessage::SendToScratchpad(arg.name).as_bytes()?)?;
+        }
+        SubCommand::ToggleScratchpad(arg) => {
+            send_message(&SocketMessage::ToggleScratchpad(arg.name).as_bytes()?)?;
+        }
+        SubCommand::CycleScratchpad(arg) => {
+            send_message(&SocketMessage::CycleScratchpad(arg.cycle_direction).as_bytes()?)?;
+        }
+        SubCommand::Mark(arg) => {
+            send_message(&SocketMessage::Mark(arg.mark).as_bytes()?)?;
+        }
+        SubCommand::FocusMark(arg) => {
+            send_message(&SocketMessage::FocusMark(arg.mark).as_bytes()?)?;
+        }
+        SubCommand::SwapWithMark(arg) => {
+            send_message(&SocketMessage::SwapWithMark(arg.mark).as_bytes()?)?;
+        }
+        SubCommand::Unmark(arg) => {
+            send_message(&SocketMessage::Unmark(arg.mark).as_bytes()?)?;
+        }
         SubCommand::WorkspaceLayout(arg) => {
             send_message(
                 &SocketMessage::WorkspaceLayout(arg.monitor, arg.workspace, arg.value)
@@ -1695,6 +1978,41 @@ fn main() -> Result<()> {
                 &SocketMessage::NamedWorkspaceTiling(arg.workspace, arg.value.into()).as_bytes()?,
             )?;
         }
+        SubCommand::WorkspaceDirectionStrategy(arg) => {
+            send_message(
+                &SocketMessage::WorkspaceDirectionStrategy(arg.monitor, arg.workspace, arg.value)
+                    .as_bytes()?,
+            )?;
+        }
+        SubCommand::NamedWorkspaceDirectionStrategy(arg) => {
+            send_message(
+                &SocketMessage::NamedWorkspaceDirectionStrategy(arg.workspace, arg.value)
+                    .as_bytes()?,
+            )?;
+        }
+        SubCommand::WorkspaceBspStrategy(arg) => {
+            send_message(
+                &SocketMessage::WorkspaceBspStrategy(arg.monitor, arg.workspace, arg.value)
+                    .as_bytes()?,
+            )?;
+        }
+        SubCommand::NamedWorkspaceBspStrategy(arg) => {
+            send_message(
+                &SocketMessage::NamedWorkspaceBspStrategy(arg.workspace, arg.value).as_bytes()?,
+            )?;
+        }
+        SubCommand::WorkspaceMaxSize(arg) => {
+            send_message(
+                &SocketMessage::WorkspaceMaxSize(arg.monitor, arg.workspace, arg.width, arg.height)
+                    .as_bytes()?,
+            )?;
+        }
+        SubCommand::NamedWorkspaceMaxSize(arg) => {
+            send_message(
+                &SocketMessage::NamedWorkspaceMaxSize(arg.workspace, arg.width, arg.height)
+                    .as_bytes()?,
+            )?;
+        }
         SubCommand::Start(arg) => {
             let mut ahk: String = String::from("autohotkey.exe");
 
@@ -1758,6 +2076,10 @@ fn main() -> Result<()> {
                 flags.push(format!("'--tcp-port={port}'"));
             }
 
+            if arg.clean_state {
+                flags.push("'--clean-state'".to_string());
+            }
+
             let script = if flags.is_empty() {
                 format!(
                     "Start-Process '{}' -WindowStyle hidden",
@@ -1858,6 +2180,9 @@ Stop-Process -Name:whkd -ErrorAction SilentlyContinue
         SubCommand::ManageRule(arg) => {
             send_message(&SocketMessage::ManageRule(arg.identifier, arg.id).as_bytes()?)?;
         }
+        SubCommand::ScratchpadRule(arg) => {
+            send_message(&SocketMessage::ScratchpadRule(arg.identifier, arg.id).as_bytes()?)?;
+        }
         SubCommand::InitialWorkspaceRule(arg) => {
             send_message(
                 &SocketMessage::InitialWorkspaceRule(
@@ -1907,6 +2232,74 @@ Stop-Process -Name:whkd -ErrorAction SilentlyContinue
                 &SocketMessage::ChangeLayoutCustom(resolve_home_path(arg.path)?).as_bytes()?,
             )?;
         }
+        SubCommand::PreviewLayout(arg) => {
+            if arg.width <= 0 || arg.height <= 0 {
+                bail!("the width and height of the preview area must be greater than zero");
+            }
+
+            let area = Rect {
+                left: 0,
+                top: 0,
+                right: arg.width,
+                bottom: arg.height,
+            };
+
+            let layout = match arg.custom_layout {
+                Some(path) => Layout::from_custom_path(resolve_home_path(path)?)?,
+                None => Layout::Default(arg.layout.unwrap_or(DefaultLayout::BSP)),
+            };
+
+            let resize_dimensions = match arg.resize {
+                Some(path) => {
+                    let path = resolve_home_path(path)?;
+                    let file = File::open(&path)
+                        .map_err(|_| anyhow!("no file found at {}", path.display()))?;
+
+                    let resize: SavedResizeDimensions = serde_json::from_reader(file)?;
+                    resize.into_ratios(&area)
+                }
+                None => vec![],
+            };
+
+            let preview = LayoutPreview {
+                layout,
+                area,
+                len: arg.containers,
+                container_padding: arg.padding,
+                layout_flip: arg.flip,
+                layout_options: LayoutOptions::default(),
+                resize_dimensions,
+            };
+
+            let rendered = preview.render(arg.format.unwrap_or_default());
+
+            match arg.output {
+                Some(output) => {
+                    let output = resolve_home_path(output)?;
+                    std::fs::write(&output, rendered)?;
+                    println!("Layout preview written to {}", output.display());
+                }
+                None => println!("{rendered}"),
+            }
+        }
+        SubCommand::ValidateLayout(arg) => {
+            let path = resolve_home_path(arg.path)?;
+            let errors = Layout::validate_custom_path(&path)?;
+
+            if errors.is_empty() {
+                println!("{} is a valid custom layout", path.display());
+            } else {
+                for error in &errors {
+                    println!("{error}");
+                }
+
+                bail!(
+                    "found {} problem(s) in the custom layout at {}",
+                    errors.len(),
+                    path.display()
+                );
+            }
+        }
         SubCommand::FlipLayout(arg) => {
             send_message(&SocketMessage::FlipLayout(arg.axis).as_bytes()?)?;
         }
@@ -1984,6 +2377,9 @@ Stop-Process -Name:whkd -ErrorAction SilentlyContinue
         SubCommand::VisibleWindows => {
             with_komorebic_socket(|| send_message(&SocketMessage::VisibleWindows.as_bytes()?))?;
         }
+        SubCommand::CheckLayout => {
+            with_komorebic_socket(|| send_message(&SocketMessage::CheckLayout.as_bytes()?))?;
+        }
         SubCommand::Query(arg) => {
             with_komorebic_socket(|| {
                 send_message(&SocketMessage::Query(arg.state_query).as_bytes()?)
@@ -2078,6 +2474,35 @@ Stop-Process -Name:whkd -ErrorAction SilentlyContinue
         SubCommand::LoadResize(arg) => {
             send_message(&SocketMessage::Load(resolve_home_path(arg.path)?).as_bytes()?)?;
         }
+        SubCommand::SaveSnapshot(arg) => {
+            send_message(&SocketMessage::SaveSnapshot(arg.name).as_bytes()?)?;
+        }
+        SubCommand::LoadSnapshot(arg) => {
+            send_message(&SocketMessage::LoadSnapshot(arg.name).as_bytes()?)?;
+        }
+        SubCommand::ListSnapshots => {
+            let snapshots = DATA_DIR.join("snapshots");
+
+            if snapshots.is_dir() {
+                let mut names = vec![];
+                for entry in std::fs::read_dir(snapshots)? {
+                    let path = entry?.path();
+                    if path
+                        .extension()
+                        .is_some_and(|extension| extension == "json")
+                    {
+                        if let Some(name) = path.file_stem() {
+                            names.push(name.to_string_lossy().to_string());
+                        }
+                    }
+                }
+
+                names.sort();
+                for name in names {
+                    println!("{name}");
+                }
+            }
+        }
         SubCommand::Subscribe(arg) => {
             send_message(&SocketMessage::AddSubscriber(arg.named_pipe).as_bytes()?)?;
         }